@@ -0,0 +1,179 @@
+//! Measures the cost of each memory barrier in cycles per operation.
+//!
+//! The barrier implementation is selected at compile time, so compare implementations with Criterion baselines:
+//!
+//! ```text
+//! cargo bench --bench barriers -- --save-baseline stdarch
+//! cargo bench --bench barriers --no-default-features --features fallback -- --baseline stdarch
+//! cargo bench --bench barriers --features x86-lock-fence -- --baseline stdarch
+//! ```
+//!
+//! On AArch64, the barrier intrinsics are only used with the `nightly` feature.
+//!
+//! This benchmark only runs on x86-64 and AArch64 hosts.
+
+#[cfg(all(
+    not(target_os = "none"),
+    any(target_arch = "aarch64", target_arch = "x86_64")
+))]
+mod bench {
+    use criterion::measurement::{Measurement, ValueFormatter};
+    use criterion::{Criterion, Throughput};
+    use mem_barrier::{BarrierKind, BarrierType, mem_barrier};
+
+    /// Measures CPU cycles.
+    ///
+    /// On x86-64, this reads the time-stamp counter, which counts at a constant rate on modern processors.
+    /// On AArch64, this reads the virtual count of the generic timer, which usually counts slower than the CPU clock.
+    pub struct Cycles;
+
+    impl Cycles {
+        #[inline(always)]
+        fn now() -> u64 {
+            cfg_if::cfg_if! {
+                if #[cfg(target_arch = "aarch64")] {
+                    let cycles: u64;
+                    // SAFETY: This just reads the virtual count after synchronizing the instruction stream.
+                    unsafe {
+                        core::arch::asm!(
+                            "isb",
+                            "mrs {}, cntvct_el0",
+                            out(reg) cycles,
+                            options(nomem, preserves_flags, nostack)
+                        );
+                    }
+                    cycles
+                } else {
+                    // SAFETY: This just reads the time-stamp counter.
+                    unsafe { core::arch::x86_64::_rdtsc() }
+                }
+            }
+        }
+    }
+
+    impl Measurement for Cycles {
+        type Intermediate = u64;
+        type Value = u64;
+
+        fn start(&self) -> Self::Intermediate {
+            Self::now()
+        }
+
+        fn end(&self, i: Self::Intermediate) -> Self::Value {
+            Self::now().wrapping_sub(i)
+        }
+
+        fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+            v1 + v2
+        }
+
+        fn zero(&self) -> Self::Value {
+            0
+        }
+
+        fn to_f64(&self, value: &Self::Value) -> f64 {
+            *value as f64
+        }
+
+        fn formatter(&self) -> &dyn ValueFormatter {
+            &CyclesFormatter
+        }
+    }
+
+    struct CyclesFormatter;
+
+    impl ValueFormatter for CyclesFormatter {
+        fn scale_values(&self, _typical_value: f64, _values: &mut [f64]) -> &'static str {
+            "cycles"
+        }
+
+        fn scale_throughputs(
+            &self,
+            _typical_value: f64,
+            throughput: &Throughput,
+            values: &mut [f64],
+        ) -> &'static str {
+            let (n, unit) = match *throughput {
+                Throughput::Bytes(n) | Throughput::BytesDecimal(n) => (n, "cycles/byte"),
+                Throughput::Bits(n) => (n, "cycles/bit"),
+                Throughput::Elements(n) | Throughput::ElementsAndBytes { elements: n, .. } => {
+                    (n, "cycles/op")
+                }
+            };
+            for value in values {
+                *value /= n as f64;
+            }
+            unit
+        }
+
+        fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+            "cycles"
+        }
+    }
+
+    /// Benchmarks every barrier type of each barrier kind.
+    ///
+    /// The barrier kind and type are constants in each benchmark, so the loop only runs the barrier instructions.
+    macro_rules! bench_barriers {
+        ($c:ident, $($kind:ident),*) => {
+            $(
+                let mut group = $c.benchmark_group(BarrierKind::$kind.to_string());
+                group.throughput(Throughput::Elements(1));
+                group.bench_function("general", |b| {
+                    b.iter(|| mem_barrier(BarrierKind::$kind, BarrierType::General))
+                });
+                group.bench_function("read", |b| {
+                    b.iter(|| mem_barrier(BarrierKind::$kind, BarrierType::Read))
+                });
+                group.bench_function("write", |b| {
+                    b.iter(|| mem_barrier(BarrierKind::$kind, BarrierType::Write))
+                });
+                group.bench_function("acquire", |b| {
+                    b.iter(|| mem_barrier(BarrierKind::$kind, BarrierType::Acquire))
+                });
+                group.bench_function("release", |b| {
+                    b.iter(|| mem_barrier(BarrierKind::$kind, BarrierType::Release))
+                });
+                group.finish();
+            )*
+        };
+    }
+
+    pub fn barriers(c: &mut Criterion<Cycles>) {
+        bench_barriers!(
+            c,
+            Mmio,
+            Smp,
+            Virt,
+            Dma,
+            DmaCompletion,
+            System,
+            NonShareable,
+            Device,
+            Compiler,
+            Instruction
+        );
+    }
+}
+
+#[cfg(all(
+    not(target_os = "none"),
+    any(target_arch = "aarch64", target_arch = "x86_64")
+))]
+criterion::criterion_group! {
+    name = benches;
+    config = criterion::Criterion::default().with_measurement(bench::Cycles);
+    targets = bench::barriers
+}
+
+#[cfg(all(
+    not(target_os = "none"),
+    any(target_arch = "aarch64", target_arch = "x86_64")
+))]
+criterion::criterion_main!(benches);
+
+#[cfg(not(all(
+    not(target_os = "none"),
+    any(target_arch = "aarch64", target_arch = "x86_64")
+)))]
+fn main() {}
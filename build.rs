@@ -0,0 +1,135 @@
+use std::env;
+
+/// Architectures on which inline assembly is stable.
+const STABLE_ASM_ARCHS: &[&str] = &[
+    "aarch64",
+    "arm",
+    "arm64ec",
+    "loongarch64",
+    "powerpc",
+    "powerpc64",
+    "riscv32",
+    "riscv64",
+    "s390x",
+    "x86",
+    "x86_64",
+];
+
+/// Emits the following `cfg`s, which select barrier implementations:
+///
+/// - `mem_barrier_asm`—Inline assembly is available.
+/// - `mem_barrier_asm_experimental_arch`—Inline assembly requires the unstable `asm_experimental_arch` feature.
+/// - `mem_barrier_arm`—The ARM architecture version, as returned by [`arm_version`].
+/// - `mem_barrier_arm_thumb1`—The ARM code is Thumb-1, as returned by [`arm_is_thumb1`].
+/// - `mem_barrier_no_lwsync`—The PowerPC core does not implement `lwsync`.
+/// - `mem_barrier_x86_fence`—The x86 processor implements the SSE2 fence instructions, as returned by [`x86_has_fence`].
+fn main() {
+    println!(
+        "cargo::rustc-check-cfg=cfg(mem_barrier_arm, values(\"v5\", \"v6\", \"v6m\", \"v7\", \"v7m\"))"
+    );
+    println!("cargo::rustc-check-cfg=cfg(mem_barrier_arm_thumb1)");
+    println!("cargo::rustc-check-cfg=cfg(mem_barrier_asm)");
+    println!("cargo::rustc-check-cfg=cfg(mem_barrier_asm_experimental_arch)");
+    println!("cargo::rustc-check-cfg=cfg(mem_barrier_no_lwsync)");
+    println!("cargo::rustc-check-cfg=cfg(mem_barrier_x86_fence)");
+
+    let target = env::var("TARGET").unwrap();
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap();
+    let target_features = env::var("CARGO_CFG_TARGET_FEATURE").unwrap_or_default();
+    let target_features = target_features.split(',').collect::<Vec<_>>();
+
+    // This names the architecture in the error for unsupported architectures.
+    println!("cargo::rustc-env=MEM_BARRIER_TARGET_ARCH={target_arch}");
+
+    // On other architectures, inline assembly requires the `nightly` feature.
+    let nightly = env::var_os("CARGO_FEATURE_NIGHTLY").is_some();
+    if STABLE_ASM_ARCHS.contains(&target_arch.as_str()) {
+        println!("cargo::rustc-cfg=mem_barrier_asm");
+    } else if nightly {
+        println!("cargo::rustc-cfg=mem_barrier_asm");
+        println!("cargo::rustc-cfg=mem_barrier_asm_experimental_arch");
+    }
+
+    if target_arch == "arm" {
+        let arm = arm_version(&target, &target_features);
+        println!("cargo::rustc-cfg=mem_barrier_arm=\"{arm}\"");
+
+        if arm_is_thumb1(&target, &target_features, arm) {
+            println!("cargo::rustc-cfg=mem_barrier_arm_thumb1");
+        }
+    }
+
+    // Book E cores with SPE, such as the e500, do not implement `lwsync`.
+    // Stable Rust does not expose the `msync` target feature to `cfg`, so we also check the target triple.
+    if target_arch == "powerpc" && (target_features.contains(&"msync") || target.ends_with("spe")) {
+        println!("cargo::rustc-cfg=mem_barrier_no_lwsync");
+    }
+
+    if x86_has_fence(&target_arch, &target_features) {
+        println!("cargo::rustc-cfg=mem_barrier_x86_fence");
+    }
+}
+
+/// Returns whether the x86 processor implements the `mfence`, `lfence`, and `sfence` instructions.
+///
+/// These instructions were introduced with SSE2.
+/// All x86-64 processors support SSE2, even if the target disables SSE for the compiler.
+fn x86_has_fence(target_arch: &str, target_features: &[&str]) -> bool {
+    match target_arch {
+        "x86" => target_features.contains(&"sse2"),
+        "x86_64" => true,
+        _ => false,
+    }
+}
+
+/// Returns the ARM architecture version relevant for selecting barrier instructions.
+///
+/// Stable Rust does not expose ARM target features to `cfg`.
+/// If they are not available, we fall back to the architecture from the target triple.
+fn arm_version(target: &str, target_features: &[&str]) -> &'static str {
+    if target_features.contains(&"mclass") {
+        if target_features.contains(&"v7") {
+            return "v7m";
+        }
+
+        return "v6m";
+    }
+
+    if target_features.contains(&"v7") {
+        return "v7";
+    }
+
+    if target_features.contains(&"v6") {
+        return "v6";
+    }
+
+    if target_features.contains(&"v5te") {
+        return "v5";
+    }
+
+    let arch = target.split('-').next().unwrap();
+    match arch {
+        "arm" if target.ends_with("-androideabi") => "v5",
+        "armv4t" | "armv5te" | "thumbv4t" | "thumbv5te" => "v5",
+        "arm" | "armv6" | "armv6k" | "thumbv6" => "v6",
+        "thumbv6m" | "thumbv8m.base" => "v6m",
+        "thumbv7m" | "thumbv7em" | "thumbv8m.main" => "v7m",
+        _ => "v7",
+    }
+}
+
+/// Returns whether the ARM target generates Thumb-1 code, which cannot encode coprocessor instructions.
+///
+/// Thumb-2 was introduced with ARMv6T2, and M-profile cores only use the barrier instructions.
+/// As for [`arm_version`], we fall back to the target triple if the target features are not available.
+fn arm_is_thumb1(target: &str, target_features: &[&str], arm: &str) -> bool {
+    if !matches!(arm, "v5" | "v6") {
+        return false;
+    }
+
+    if target_features.contains(&"thumb-mode") {
+        return !target_features.contains(&"thumb2");
+    }
+
+    target.starts_with("thumb")
+}
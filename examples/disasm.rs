@@ -0,0 +1,98 @@
+//! One exported function per memory barrier.
+//!
+//! The `disasm` integration test disassembles these functions to check the emitted instructions.
+//! Each function is named `barrier_<kind>_<type>`, after the [`Display`](core::fmt::Display) implementations of the barrier kind and type.
+//! `barrier_full_system` runs [`full_system_barrier`].
+
+#![cfg_attr(target_os = "none", no_std)]
+#![cfg_attr(target_os = "none", no_main)]
+
+use mem_barrier::{BarrierKind, BarrierType, full_system_barrier, mem_barrier};
+
+macro_rules! barriers {
+    ($($name:ident => $kind:ident, $ty:ident;)*) => {
+        $(
+            #[unsafe(no_mangle)]
+            pub extern "C" fn $name() {
+                mem_barrier(BarrierKind::$kind, BarrierType::$ty);
+            }
+        )*
+
+        fn main() {
+            $($name();)*
+            barrier_full_system();
+        }
+    };
+}
+
+barriers! {
+    barrier_mmio_general => Mmio, General;
+    barrier_mmio_read => Mmio, Read;
+    barrier_mmio_write => Mmio, Write;
+    barrier_mmio_acquire => Mmio, Acquire;
+    barrier_mmio_release => Mmio, Release;
+    barrier_smp_general => Smp, General;
+    barrier_smp_read => Smp, Read;
+    barrier_smp_write => Smp, Write;
+    barrier_smp_acquire => Smp, Acquire;
+    barrier_smp_release => Smp, Release;
+    barrier_virt_general => Virt, General;
+    barrier_virt_read => Virt, Read;
+    barrier_virt_write => Virt, Write;
+    barrier_virt_acquire => Virt, Acquire;
+    barrier_virt_release => Virt, Release;
+    barrier_dma_general => Dma, General;
+    barrier_dma_read => Dma, Read;
+    barrier_dma_write => Dma, Write;
+    barrier_dma_acquire => Dma, Acquire;
+    barrier_dma_release => Dma, Release;
+    barrier_dma_completion_general => DmaCompletion, General;
+    barrier_dma_completion_read => DmaCompletion, Read;
+    barrier_dma_completion_write => DmaCompletion, Write;
+    barrier_dma_completion_acquire => DmaCompletion, Acquire;
+    barrier_dma_completion_release => DmaCompletion, Release;
+    barrier_system_general => System, General;
+    barrier_system_read => System, Read;
+    barrier_system_write => System, Write;
+    barrier_system_acquire => System, Acquire;
+    barrier_system_release => System, Release;
+    barrier_non_shareable_general => NonShareable, General;
+    barrier_non_shareable_read => NonShareable, Read;
+    barrier_non_shareable_write => NonShareable, Write;
+    barrier_non_shareable_acquire => NonShareable, Acquire;
+    barrier_non_shareable_release => NonShareable, Release;
+    barrier_device_general => Device, General;
+    barrier_device_read => Device, Read;
+    barrier_device_write => Device, Write;
+    barrier_device_acquire => Device, Acquire;
+    barrier_device_release => Device, Release;
+    barrier_compiler_general => Compiler, General;
+    barrier_compiler_read => Compiler, Read;
+    barrier_compiler_write => Compiler, Write;
+    barrier_compiler_acquire => Compiler, Acquire;
+    barrier_compiler_release => Compiler, Release;
+    barrier_instruction_general => Instruction, General;
+    barrier_instruction_read => Instruction, Read;
+    barrier_instruction_write => Instruction, Write;
+    barrier_instruction_acquire => Instruction, Acquire;
+    barrier_instruction_release => Instruction, Release;
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn barrier_full_system() {
+    full_system_barrier();
+}
+
+#[cfg(target_os = "none")]
+#[unsafe(no_mangle)]
+extern "C" fn _start() -> ! {
+    main();
+
+    loop {}
+}
+
+#[cfg(target_os = "none")]
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
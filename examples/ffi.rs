@@ -0,0 +1,62 @@
+//! Calling `mem_barrier_ffi` as C code would.
+//!
+//! The corresponding C declarations are:
+//!
+//! ```c
+//! #include <stdint.h>
+//!
+//! enum mem_barrier_kind {
+//!     MEM_BARRIER_KIND_MMIO = 0,
+//!     MEM_BARRIER_KIND_SMP = 1,
+//!     MEM_BARRIER_KIND_DMA = 2,
+//!     MEM_BARRIER_KIND_DMA_COMPLETION = 3,
+//!     MEM_BARRIER_KIND_NON_SHAREABLE = 4,
+//!     MEM_BARRIER_KIND_DEVICE = 5,
+//!     MEM_BARRIER_KIND_COMPILER = 6,
+//!     MEM_BARRIER_KIND_INSTRUCTION = 7,
+//!     MEM_BARRIER_KIND_VIRT = 8,
+//!     MEM_BARRIER_KIND_SYSTEM = 9,
+//! };
+//!
+//! enum mem_barrier_type {
+//!     MEM_BARRIER_TYPE_GENERAL = 0,
+//!     MEM_BARRIER_TYPE_READ = 1,
+//!     MEM_BARRIER_TYPE_WRITE = 2,
+//!     MEM_BARRIER_TYPE_ACQUIRE = 3,
+//!     MEM_BARRIER_TYPE_RELEASE = 4,
+//! };
+//!
+//! void mem_barrier_ffi(uint8_t kind, uint8_t ty);
+//! ```
+
+#![cfg_attr(target_os = "none", no_std)]
+#![cfg_attr(target_os = "none", no_main)]
+
+// Link this crate, which exports `mem_barrier_ffi`.
+use mem_barrier as _;
+
+unsafe extern "C" {
+    fn mem_barrier_ffi(kind: u8, ty: u8);
+}
+
+fn main() {
+    // SAFETY: `mem_barrier_ffi` accepts any values.
+    unsafe {
+        // MEM_BARRIER_KIND_SMP, MEM_BARRIER_TYPE_WRITE
+        mem_barrier_ffi(1, 2);
+    }
+}
+
+#[cfg(target_os = "none")]
+#[unsafe(no_mangle)]
+extern "C" fn _start() -> ! {
+    main();
+
+    loop {}
+}
+
+#[cfg(target_os = "none")]
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
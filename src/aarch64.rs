@@ -0,0 +1,305 @@
+//! AArch64-specific barriers.
+
+use crate::BarrierType;
+
+/// An instruction synchronization barrier.
+///
+/// This runs an [ISB] instruction.
+/// It flushes the pipeline, so that instructions after the barrier are fetched only once the barrier completes.
+/// This makes context-changing operations before the barrier, such as writing `TTBR0_EL1` or `VBAR_EL1`, visible to later instructions.
+///
+/// This is the same as [`mem_barrier`](fn@crate::mem_barrier) with [`BarrierKind::Instruction`](crate::BarrierKind::Instruction).
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(target_arch = "aarch64")]
+/// # {
+/// use mem_barrier::{BarrierKind, BarrierType, aarch64, mem_barrier};
+///
+/// // Write TTBR0_EL1 here.
+///
+/// // Wait for earlier memory accesses to complete.
+/// mem_barrier(BarrierKind::Mmio, BarrierType::General);
+/// // Make the new translation table visible to later instructions.
+/// aarch64::isb();
+/// # }
+/// ```
+///
+/// [ISB]: https://developer.arm.com/documentation/ddi0602/2025-09/Base-Instructions/ISB--Instruction-synchronization-barrier-
+#[inline(always)]
+pub fn isb() {
+    crate::mem_barrier(crate::BarrierKind::Instruction, BarrierType::General);
+}
+
+/// A speculation barrier.
+///
+/// This prevents speculative execution of instructions after the barrier until the barrier completes.
+/// It is used in mitigations against speculative execution side channels, such as Spectre.
+///
+/// # Current implementation
+///
+/// With FEAT_SB, this runs an [SB] instruction.
+/// Otherwise, this runs a `DSB SY` instruction followed by an `ISB` instruction, which is the recommended fallback sequence.
+///
+/// [SB]: https://developer.arm.com/documentation/ddi0602/2025-09/Base-Instructions/SB--Speculation-barrier-
+#[inline(always)]
+pub fn speculation_barrier() {
+    cfg_if::cfg_if! {
+        if #[cfg(any(miri, feature = "loom"))] {
+            // Miri cannot run inline assembly and does not speculate, and loom cannot reason about inline assembly.
+            crate::arch::fallback::fence(core::sync::atomic::Ordering::SeqCst);
+        } else if #[cfg(target_feature = "sb")] {
+            // SAFETY: This is just a speculation barrier.
+            unsafe {
+                core::arch::asm!("sb", options(preserves_flags, nostack));
+            }
+        } else {
+            // SAFETY: This is just a data synchronization barrier and an instruction synchronization barrier.
+            unsafe {
+                core::arch::asm!("dsb sy", "isb", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
+
+/// A consumption of speculative data barrier.
+///
+/// This runs a [CSDB] instruction.
+/// It prevents instructions after the barrier from using the results of speculative data value prediction or of conditional select and conditional move instructions whose condition is still unresolved.
+/// It is used in mitigations against speculative execution side channels, such as Spectre variant 1.
+///
+/// This is not a memory barrier and does not order memory accesses.
+///
+/// # Current implementation
+///
+/// This runs the `HINT #20` encoding of CSDB, which older assemblers accept as well.
+/// Cores that do not implement CSDB execute it as a `NOP`.
+///
+/// [CSDB]: https://developer.arm.com/documentation/ddi0602/2025-09/Base-Instructions/CSDB--Consumption-of-speculative-data-barrier-
+#[inline(always)]
+pub fn csdb() {
+    cfg_if::cfg_if! {
+        if #[cfg(any(miri, feature = "loom"))] {
+            // Miri cannot run inline assembly and does not speculate, and loom cannot reason about inline assembly.
+            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+        } else {
+            // SAFETY: This is just a speculation barrier.
+            unsafe {
+                core::arch::asm!("hint #20", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
+
+/// The shareability domain of a [`dsb`].
+///
+/// The domain selects the observers for which the barrier waits for memory accesses to complete.
+/// See [_Shareability domains_](crate#shareability-domains) for which domain corresponds to which sharing scenario.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Domain {
+    /// The non-shareable domain (`NSH`), which only contains the current CPU.
+    NonShareable,
+
+    /// The inner shareable domain (`ISH`), which usually contains all CPUs running the same OS.
+    InnerShareable,
+
+    /// The outer shareable domain (`OSH`), which also contains coherent agents such as CCIX- or CXL-attached devices.
+    OuterShareable,
+
+    /// The full system (`SY`), which contains all agents in the system.
+    #[default]
+    FullSystem,
+}
+
+/// A data synchronization barrier.
+///
+/// This runs a [DSB] instruction with the given domain.
+/// Read and acquire barriers use the load-only option (`LD`), and write barriers use the store-only option (`ST`).
+///
+/// Unlike the `DMB` instructions run by most kinds of [`mem_barrier`](fn@crate::mem_barrier), this is a completion barrier:
+/// It does not return until memory accesses before the barrier have completed for all observers in the domain.
+/// This is required before a side effect that memory ordering does not cover, such as gating a clock, entering a low-power state with `WFI`, or changing the power state of a device.
+///
+/// [`BarrierKind::Mmio`](crate::BarrierKind::Mmio) barriers are the same as `dsb(Domain::FullSystem, ty)`, and [`BarrierKind::DmaCompletion`](crate::BarrierKind::DmaCompletion) barriers are the same as `dsb(Domain::OuterShareable, ty)`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(target_arch = "aarch64")]
+/// # {
+/// use mem_barrier::BarrierType;
+/// use mem_barrier::aarch64::{Domain, dsb};
+///
+/// // Write to the power controller here.
+///
+/// // Wait for the write to complete before entering the low-power state.
+/// dsb(Domain::FullSystem, BarrierType::Write);
+/// // Run WFI here.
+/// # }
+/// ```
+///
+/// [DSB]: https://developer.arm.com/documentation/ddi0602/2025-09/Base-Instructions/DSB--Data-synchronization-barrier-
+#[doc(alias = "data_sync_barrier")]
+#[inline(always)]
+pub fn dsb(domain: Domain, ty: BarrierType) {
+    cfg_if::cfg_if! {
+        if #[cfg(any(miri, feature = "loom"))] {
+            // Miri cannot run inline assembly, and loom cannot reason about it.
+            let _ = (domain, ty);
+            crate::arch::fallback::fence(core::sync::atomic::Ordering::SeqCst);
+        } else if #[cfg(all(feature = "stdarch", feature = "nightly"))] {
+            use core::arch::aarch64::{
+                __dsb, ISH, ISHLD, ISHST, LD, NSH, NSHLD, NSHST, OSH, OSHLD, OSHST, ST, SY,
+            };
+
+            // SAFETY: This is just a data synchronization barrier.
+            unsafe {
+                match (domain, ty) {
+                    (Domain::NonShareable, BarrierType::General | BarrierType::Release) => {
+                        __dsb(NSH)
+                    }
+                    (Domain::NonShareable, BarrierType::Read | BarrierType::Acquire) => {
+                        __dsb(NSHLD)
+                    }
+                    (Domain::NonShareable, BarrierType::Write) => __dsb(NSHST),
+                    (Domain::InnerShareable, BarrierType::General | BarrierType::Release) => {
+                        __dsb(ISH)
+                    }
+                    (Domain::InnerShareable, BarrierType::Read | BarrierType::Acquire) => {
+                        __dsb(ISHLD)
+                    }
+                    (Domain::InnerShareable, BarrierType::Write) => __dsb(ISHST),
+                    (Domain::OuterShareable, BarrierType::General | BarrierType::Release) => {
+                        __dsb(OSH)
+                    }
+                    (Domain::OuterShareable, BarrierType::Read | BarrierType::Acquire) => {
+                        __dsb(OSHLD)
+                    }
+                    (Domain::OuterShareable, BarrierType::Write) => __dsb(OSHST),
+                    (Domain::FullSystem, BarrierType::General | BarrierType::Release) => __dsb(SY),
+                    (Domain::FullSystem, BarrierType::Read | BarrierType::Acquire) => __dsb(LD),
+                    (Domain::FullSystem, BarrierType::Write) => __dsb(ST),
+                }
+            }
+        } else {
+            // SAFETY: This is just a data synchronization barrier.
+            unsafe {
+                match (domain, ty) {
+                    (Domain::NonShareable, BarrierType::General | BarrierType::Release) => {
+                        core::arch::asm!("dsb nsh", options(preserves_flags, nostack));
+                    }
+                    (Domain::NonShareable, BarrierType::Read | BarrierType::Acquire) => {
+                        core::arch::asm!("dsb nshld", options(preserves_flags, nostack));
+                    }
+                    (Domain::NonShareable, BarrierType::Write) => {
+                        core::arch::asm!("dsb nshst", options(preserves_flags, nostack));
+                    }
+                    (Domain::InnerShareable, BarrierType::General | BarrierType::Release) => {
+                        core::arch::asm!("dsb ish", options(preserves_flags, nostack));
+                    }
+                    (Domain::InnerShareable, BarrierType::Read | BarrierType::Acquire) => {
+                        core::arch::asm!("dsb ishld", options(preserves_flags, nostack));
+                    }
+                    (Domain::InnerShareable, BarrierType::Write) => {
+                        core::arch::asm!("dsb ishst", options(preserves_flags, nostack));
+                    }
+                    (Domain::OuterShareable, BarrierType::General | BarrierType::Release) => {
+                        core::arch::asm!("dsb osh", options(preserves_flags, nostack));
+                    }
+                    (Domain::OuterShareable, BarrierType::Read | BarrierType::Acquire) => {
+                        core::arch::asm!("dsb oshld", options(preserves_flags, nostack));
+                    }
+                    (Domain::OuterShareable, BarrierType::Write) => {
+                        core::arch::asm!("dsb oshst", options(preserves_flags, nostack));
+                    }
+                    (Domain::FullSystem, BarrierType::General | BarrierType::Release) => {
+                        core::arch::asm!("dsb sy", options(preserves_flags, nostack));
+                    }
+                    (Domain::FullSystem, BarrierType::Read | BarrierType::Acquire) => {
+                        core::arch::asm!("dsb ld", options(preserves_flags, nostack));
+                    }
+                    (Domain::FullSystem, BarrierType::Write) => {
+                        core::arch::asm!("dsb st", options(preserves_flags, nostack));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Sends an event to all CPUs.
+///
+/// This runs a [SEV] instruction, which wakes up CPUs waiting in [`wait_for_event`].
+///
+/// This is not a memory barrier, and `DMB` instructions do not order it either.
+/// To make memory writes visible to the woken CPUs, issue a [`dsb`] before sending the event.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(target_arch = "aarch64")]
+/// # {
+/// use core::sync::atomic::{AtomicBool, Ordering};
+///
+/// use mem_barrier::BarrierType;
+/// use mem_barrier::aarch64::{Domain, dsb, send_event, wait_for_event};
+///
+/// static READY: AtomicBool = AtomicBool::new(false);
+///
+/// // Publish the data, then wake up the waiting CPUs.
+/// READY.store(true, Ordering::Release);
+/// dsb(Domain::InnerShareable, BarrierType::Write);
+/// send_event();
+///
+/// // On the waiting CPU, sleep until the data is published.
+/// while !READY.load(Ordering::Acquire) {
+///     wait_for_event();
+/// }
+/// # }
+/// ```
+///
+/// [SEV]: https://developer.arm.com/documentation/ddi0602/2025-09/Base-Instructions/SEV--Send-event-
+#[doc(alias = "sev")]
+#[inline(always)]
+pub fn send_event() {
+    cfg_if::cfg_if! {
+        if #[cfg(any(miri, feature = "loom"))] {
+            // Miri cannot run inline assembly, and loom cannot reason about it.
+            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+        } else {
+            // SAFETY: This is just a hint.
+            unsafe {
+                core::arch::asm!("sev", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
+
+/// Waits for an event.
+///
+/// This runs a [WFE] instruction, which may put the CPU into a low-power state until an event, such as one sent by [`send_event`], or an interrupt wakes it up.
+///
+/// This is not a memory barrier.
+/// The CPU may also wake up spuriously, so callers have to check their wakeup condition in a loop.
+/// See [`send_event`] for an example.
+///
+/// [WFE]: https://developer.arm.com/documentation/ddi0602/2025-09/Base-Instructions/WFE--Wait-for-event-
+#[doc(alias = "wfe")]
+#[inline(always)]
+pub fn wait_for_event() {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "loom")] {
+            // This lets loom schedule other threads while waiting.
+            loom::hint::spin_loop();
+        } else if #[cfg(miri)] {
+            // Miri cannot run inline assembly.
+            core::hint::spin_loop();
+        } else {
+            // SAFETY: This is just a hint.
+            unsafe {
+                core::arch::asm!("wfe", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
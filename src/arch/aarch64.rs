@@ -1,60 +1,161 @@
 use super::CpuBarrierKind;
 use crate::BarrierType;
 
+// Load-only barrier options order earlier loads before later loads and stores, which makes them suitable for acquire barriers.
+// Store-only barrier options only order stores against each other, so release barriers need the full barrier.
+
 #[cfg(all(feature = "stdarch", feature = "nightly"))]
-#[inline]
+#[inline(always)]
 pub fn mem_barrier(kind: CpuBarrierKind, ty: BarrierType) {
-    use core::arch::aarch64::{__dmb, __dsb, ISH, ISHLD, ISHST, LD, OSH, OSHLD, OSHST, ST, SY};
+    use core::arch::aarch64::{
+        __dmb, __dsb, ISH, ISHLD, ISHST, LD, NSH, NSHLD, NSHST, OSH, OSHLD, OSHST, ST, SY,
+    };
 
     // SAFETY: This is just a data synchronization barrier or data memory barrier.
     unsafe {
         match (kind, ty) {
-            (CpuBarrierKind::Mmio, BarrierType::General) => __dsb(SY),
-            (CpuBarrierKind::Mmio, BarrierType::Read) => __dsb(LD),
+            (CpuBarrierKind::Mmio, BarrierType::General | BarrierType::Release) => __dsb(SY),
+            (CpuBarrierKind::Mmio, BarrierType::Read | BarrierType::Acquire) => __dsb(LD),
             (CpuBarrierKind::Mmio, BarrierType::Write) => __dsb(ST),
-            (CpuBarrierKind::Smp, BarrierType::General) => __dmb(ISH),
-            (CpuBarrierKind::Smp, BarrierType::Read) => __dmb(ISHLD),
+            (CpuBarrierKind::Smp, BarrierType::General | BarrierType::Release) => __dmb(ISH),
+            (CpuBarrierKind::Smp, BarrierType::Read | BarrierType::Acquire) => __dmb(ISHLD),
             (CpuBarrierKind::Smp, BarrierType::Write) => __dmb(ISHST),
-            (CpuBarrierKind::Dma, BarrierType::General) => __dmb(OSH),
-            (CpuBarrierKind::Dma, BarrierType::Read) => __dmb(OSHLD),
+            (CpuBarrierKind::Dma, BarrierType::General | BarrierType::Release) => __dmb(OSH),
+            (CpuBarrierKind::Dma, BarrierType::Read | BarrierType::Acquire) => __dmb(OSHLD),
             (CpuBarrierKind::Dma, BarrierType::Write) => __dmb(OSHST),
+            (CpuBarrierKind::DmaCompletion, BarrierType::General | BarrierType::Release) => {
+                __dsb(OSH)
+            }
+            (CpuBarrierKind::DmaCompletion, BarrierType::Read | BarrierType::Acquire) => {
+                __dsb(OSHLD)
+            }
+            (CpuBarrierKind::DmaCompletion, BarrierType::Write) => __dsb(OSHST),
+            (CpuBarrierKind::System, BarrierType::General | BarrierType::Release) => __dmb(SY),
+            (CpuBarrierKind::System, BarrierType::Read | BarrierType::Acquire) => __dmb(LD),
+            (CpuBarrierKind::System, BarrierType::Write) => __dmb(ST),
+            (CpuBarrierKind::NonShareable, BarrierType::General | BarrierType::Release) => {
+                __dmb(NSH)
+            }
+            (CpuBarrierKind::NonShareable, BarrierType::Read | BarrierType::Acquire) => {
+                __dmb(NSHLD)
+            }
+            (CpuBarrierKind::NonShareable, BarrierType::Write) => __dmb(NSHST),
         }
     }
 }
 
+// The inline assembly implementation is always compiled, so that `crate::mem_barrier_asm` can compare it against the intrinsics.
+
 #[cfg(not(all(feature = "stdarch", feature = "nightly")))]
-#[inline]
-pub fn mem_barrier(kind: CpuBarrierKind, ty: BarrierType) {
+pub use self::{instruction_barrier_asm as instruction_barrier, mem_barrier_asm as mem_barrier};
+
+#[inline(always)]
+pub fn mem_barrier_asm(kind: CpuBarrierKind, ty: BarrierType) {
     // SAFETY: This is just a data synchronization barrier or data memory barrier.
     unsafe {
         match (kind, ty) {
-            (CpuBarrierKind::Mmio, BarrierType::General) => {
+            (CpuBarrierKind::Mmio, BarrierType::General | BarrierType::Release) => {
                 core::arch::asm!("dsb sy", options(preserves_flags, nostack));
             }
-            (CpuBarrierKind::Mmio, BarrierType::Read) => {
+            (CpuBarrierKind::Mmio, BarrierType::Read | BarrierType::Acquire) => {
                 core::arch::asm!("dsb ld", options(preserves_flags, nostack));
             }
             (CpuBarrierKind::Mmio, BarrierType::Write) => {
                 core::arch::asm!("dsb st", options(preserves_flags, nostack));
             }
-            (CpuBarrierKind::Smp, BarrierType::General) => {
+            (CpuBarrierKind::Smp, BarrierType::General | BarrierType::Release) => {
                 core::arch::asm!("dmb ish", options(preserves_flags, nostack));
             }
-            (CpuBarrierKind::Smp, BarrierType::Read) => {
+            (CpuBarrierKind::Smp, BarrierType::Read | BarrierType::Acquire) => {
                 core::arch::asm!("dmb ishld", options(preserves_flags, nostack));
             }
             (CpuBarrierKind::Smp, BarrierType::Write) => {
                 core::arch::asm!("dmb ishst", options(preserves_flags, nostack));
             }
-            (CpuBarrierKind::Dma, BarrierType::General) => {
+            (CpuBarrierKind::Dma, BarrierType::General | BarrierType::Release) => {
                 core::arch::asm!("dmb osh", options(preserves_flags, nostack));
             }
-            (CpuBarrierKind::Dma, BarrierType::Read) => {
+            (CpuBarrierKind::Dma, BarrierType::Read | BarrierType::Acquire) => {
                 core::arch::asm!("dmb oshld", options(preserves_flags, nostack));
             }
             (CpuBarrierKind::Dma, BarrierType::Write) => {
                 core::arch::asm!("dmb oshst", options(preserves_flags, nostack));
             }
+            (CpuBarrierKind::DmaCompletion, BarrierType::General | BarrierType::Release) => {
+                core::arch::asm!("dsb osh", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::DmaCompletion, BarrierType::Read | BarrierType::Acquire) => {
+                core::arch::asm!("dsb oshld", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::DmaCompletion, BarrierType::Write) => {
+                core::arch::asm!("dsb oshst", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::System, BarrierType::General | BarrierType::Release) => {
+                core::arch::asm!("dmb sy", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::System, BarrierType::Read | BarrierType::Acquire) => {
+                core::arch::asm!("dmb ld", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::System, BarrierType::Write) => {
+                core::arch::asm!("dmb st", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::NonShareable, BarrierType::General | BarrierType::Release) => {
+                core::arch::asm!("dmb nsh", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::NonShareable, BarrierType::Read | BarrierType::Acquire) => {
+                core::arch::asm!("dmb nshld", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::NonShareable, BarrierType::Write) => {
+                core::arch::asm!("dmb nshst", options(preserves_flags, nostack));
+            }
         }
     }
 }
+
+#[cfg(all(feature = "stdarch", feature = "nightly"))]
+#[inline(always)]
+pub fn instruction_barrier() {
+    use core::arch::aarch64::{__isb, SY};
+
+    // SAFETY: This is just an instruction synchronization barrier.
+    unsafe { __isb(SY) }
+}
+
+#[inline(always)]
+pub fn instruction_barrier_asm() {
+    // SAFETY: This is just an instruction synchronization barrier.
+    unsafe {
+        core::arch::asm!("isb", options(preserves_flags, nostack));
+    }
+}
+
+pub const fn mnemonic(kind: CpuBarrierKind, ty: BarrierType) -> &'static str {
+    match (kind, ty) {
+        (CpuBarrierKind::Mmio, BarrierType::General | BarrierType::Release) => "dsb sy",
+        (CpuBarrierKind::Mmio, BarrierType::Read | BarrierType::Acquire) => "dsb ld",
+        (CpuBarrierKind::Mmio, BarrierType::Write) => "dsb st",
+        (CpuBarrierKind::Smp, BarrierType::General | BarrierType::Release) => "dmb ish",
+        (CpuBarrierKind::Smp, BarrierType::Read | BarrierType::Acquire) => "dmb ishld",
+        (CpuBarrierKind::Smp, BarrierType::Write) => "dmb ishst",
+        (CpuBarrierKind::Dma, BarrierType::General | BarrierType::Release) => "dmb osh",
+        (CpuBarrierKind::Dma, BarrierType::Read | BarrierType::Acquire) => "dmb oshld",
+        (CpuBarrierKind::Dma, BarrierType::Write) => "dmb oshst",
+        (CpuBarrierKind::DmaCompletion, BarrierType::General | BarrierType::Release) => "dsb osh",
+        (CpuBarrierKind::DmaCompletion, BarrierType::Read | BarrierType::Acquire) => "dsb oshld",
+        (CpuBarrierKind::DmaCompletion, BarrierType::Write) => "dsb oshst",
+        (CpuBarrierKind::System, BarrierType::General | BarrierType::Release) => "dmb sy",
+        (CpuBarrierKind::System, BarrierType::Read | BarrierType::Acquire) => "dmb ld",
+        (CpuBarrierKind::System, BarrierType::Write) => "dmb st",
+        (CpuBarrierKind::NonShareable, BarrierType::General | BarrierType::Release) => "dmb nsh",
+        (CpuBarrierKind::NonShareable, BarrierType::Read | BarrierType::Acquire) => "dmb nshld",
+        (CpuBarrierKind::NonShareable, BarrierType::Write) => "dmb nshst",
+    }
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    "isb"
+}
+
+pub const USES_STDARCH: bool = cfg!(all(feature = "stdarch", feature = "nightly"));
+
+pub const USES_NIGHTLY_INTRINSICS: bool = cfg!(all(feature = "stdarch", feature = "nightly"));
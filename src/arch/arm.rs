@@ -0,0 +1,450 @@
+// ARMv5 and earlier have no memory barriers.
+// These cores are uniprocessor, though, so only devices can observe reordering.
+// For those, we drain the write buffer via CP15.
+//
+// The inline assembly implementation is always compiled, so that `crate::mem_barrier_asm` can compare it against the intrinsics.
+// Without the intrinsics, it is the only implementation.
+#[cfg(not(all(
+    not(mem_barrier_arm = "v5"),
+    not(mem_barrier_arm_thumb1),
+    feature = "stdarch",
+    feature = "nightly"
+)))]
+pub use self::{instruction_barrier_asm as instruction_barrier, mem_barrier_asm as mem_barrier};
+use super::CpuBarrierKind;
+use crate::BarrierType;
+
+#[cfg(mem_barrier_arm = "v5")]
+#[inline(always)]
+pub fn mem_barrier_asm(kind: CpuBarrierKind, _ty: BarrierType) {
+    match kind {
+        CpuBarrierKind::Mmio | CpuBarrierKind::Dma => cp15::data_synchronization_barrier(),
+        CpuBarrierKind::Smp => crate::compiler_barrier(),
+    }
+}
+
+// ARMv6 has no barrier instructions.
+// Instead, barriers are CP15 operations, which do not distinguish between reads and writes.
+
+#[cfg(all(
+    mem_barrier_arm = "v6",
+    not(mem_barrier_arm_thumb1),
+    feature = "stdarch",
+    feature = "nightly"
+))]
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, _ty: BarrierType) {
+    use core::arch::arm::{__dmb, __dsb, SY};
+
+    // SAFETY: This is just a data synchronization barrier or data memory barrier.
+    unsafe {
+        match kind {
+            CpuBarrierKind::Mmio => __dsb(SY),
+            CpuBarrierKind::Smp | CpuBarrierKind::Dma => __dmb(SY),
+        }
+    }
+}
+
+#[cfg(mem_barrier_arm = "v6")]
+#[inline(always)]
+pub fn mem_barrier_asm(kind: CpuBarrierKind, _ty: BarrierType) {
+    match kind {
+        CpuBarrierKind::Mmio => cp15::data_synchronization_barrier(),
+        CpuBarrierKind::Smp | CpuBarrierKind::Dma => cp15::data_memory_barrier(),
+    }
+}
+
+// M-profile cores only support the full-system barrier option.
+// We omit the option, which is equivalent to `sy`.
+
+#[cfg(all(
+    any(mem_barrier_arm = "v6m", mem_barrier_arm = "v7m"),
+    feature = "stdarch",
+    feature = "nightly"
+))]
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, _ty: BarrierType) {
+    use core::arch::arm::{__dmb, __dsb, SY};
+
+    // SAFETY: This is just a data synchronization barrier or data memory barrier.
+    unsafe {
+        match kind {
+            CpuBarrierKind::Mmio => __dsb(SY),
+            CpuBarrierKind::Smp | CpuBarrierKind::Dma => __dmb(SY),
+        }
+    }
+}
+
+#[cfg(any(mem_barrier_arm = "v6m", mem_barrier_arm = "v7m"))]
+#[inline(always)]
+pub fn mem_barrier_asm(kind: CpuBarrierKind, _ty: BarrierType) {
+    // SAFETY: This is just a data synchronization barrier or data memory barrier.
+    unsafe {
+        match kind {
+            CpuBarrierKind::Mmio => {
+                core::arch::asm!("dsb", options(preserves_flags, nostack));
+            }
+            CpuBarrierKind::Smp | CpuBarrierKind::Dma => {
+                core::arch::asm!("dmb", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
+
+// ARMv7 has no load-only barrier options, so read, acquire, and release barriers use the full barrier.
+
+#[cfg(all(mem_barrier_arm = "v7", feature = "stdarch", feature = "nightly"))]
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, ty: BarrierType) {
+    use core::arch::arm::{__dmb, __dsb, ISH, ISHST, OSH, OSHST, ST, SY};
+
+    // SAFETY: This is just a data synchronization barrier or data memory barrier.
+    unsafe {
+        match (kind, ty) {
+            (
+                CpuBarrierKind::Mmio,
+                BarrierType::General
+                | BarrierType::Read
+                | BarrierType::Acquire
+                | BarrierType::Release,
+            ) => __dsb(SY),
+            (CpuBarrierKind::Mmio, BarrierType::Write) => __dsb(ST),
+            (
+                CpuBarrierKind::Smp,
+                BarrierType::General
+                | BarrierType::Read
+                | BarrierType::Acquire
+                | BarrierType::Release,
+            ) => __dmb(ISH),
+            (CpuBarrierKind::Smp, BarrierType::Write) => __dmb(ISHST),
+            (
+                CpuBarrierKind::Dma,
+                BarrierType::General
+                | BarrierType::Read
+                | BarrierType::Acquire
+                | BarrierType::Release,
+            ) => __dmb(OSH),
+            (CpuBarrierKind::Dma, BarrierType::Write) => __dmb(OSHST),
+        }
+    }
+}
+
+#[cfg(mem_barrier_arm = "v7")]
+#[inline(always)]
+pub fn mem_barrier_asm(kind: CpuBarrierKind, ty: BarrierType) {
+    // SAFETY: This is just a data synchronization barrier or data memory barrier.
+    unsafe {
+        match (kind, ty) {
+            (
+                CpuBarrierKind::Mmio,
+                BarrierType::General
+                | BarrierType::Read
+                | BarrierType::Acquire
+                | BarrierType::Release,
+            ) => {
+                core::arch::asm!("dsb sy", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::Mmio, BarrierType::Write) => {
+                core::arch::asm!("dsb st", options(preserves_flags, nostack));
+            }
+            (
+                CpuBarrierKind::Smp,
+                BarrierType::General
+                | BarrierType::Read
+                | BarrierType::Acquire
+                | BarrierType::Release,
+            ) => {
+                core::arch::asm!("dmb ish", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::Smp, BarrierType::Write) => {
+                core::arch::asm!("dmb ishst", options(preserves_flags, nostack));
+            }
+            (
+                CpuBarrierKind::Dma,
+                BarrierType::General
+                | BarrierType::Read
+                | BarrierType::Acquire
+                | BarrierType::Release,
+            ) => {
+                core::arch::asm!("dmb osh", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::Dma, BarrierType::Write) => {
+                core::arch::asm!("dmb oshst", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
+
+// Data synchronization barriers for `crate::arm::dsb` follow the same architecture versions.
+// Only ARMv7 supports shareability domains, and no version supports load-only options.
+
+#[cfg(mem_barrier_arm = "v5")]
+#[inline(always)]
+pub fn dsb(_domain: crate::arm::Domain, _ty: BarrierType) {
+    cp15::data_synchronization_barrier();
+}
+
+#[cfg(all(
+    mem_barrier_arm = "v6",
+    not(mem_barrier_arm_thumb1),
+    feature = "stdarch",
+    feature = "nightly"
+))]
+#[inline(always)]
+pub fn dsb(_domain: crate::arm::Domain, _ty: BarrierType) {
+    use core::arch::arm::{__dsb, SY};
+
+    // SAFETY: This is just a data synchronization barrier.
+    unsafe { __dsb(SY) }
+}
+
+#[cfg(all(
+    mem_barrier_arm = "v6",
+    any(
+        mem_barrier_arm_thumb1,
+        not(all(feature = "stdarch", feature = "nightly"))
+    )
+))]
+#[inline(always)]
+pub fn dsb(_domain: crate::arm::Domain, _ty: BarrierType) {
+    cp15::data_synchronization_barrier();
+}
+
+#[cfg(all(
+    any(mem_barrier_arm = "v6m", mem_barrier_arm = "v7m"),
+    feature = "stdarch",
+    feature = "nightly"
+))]
+#[inline(always)]
+pub fn dsb(_domain: crate::arm::Domain, _ty: BarrierType) {
+    use core::arch::arm::{__dsb, SY};
+
+    // SAFETY: This is just a data synchronization barrier.
+    unsafe { __dsb(SY) }
+}
+
+#[cfg(all(
+    any(mem_barrier_arm = "v6m", mem_barrier_arm = "v7m"),
+    not(all(feature = "stdarch", feature = "nightly"))
+))]
+#[inline(always)]
+pub fn dsb(_domain: crate::arm::Domain, _ty: BarrierType) {
+    // SAFETY: This is just a data synchronization barrier.
+    unsafe {
+        core::arch::asm!("dsb", options(preserves_flags, nostack));
+    }
+}
+
+#[cfg(all(mem_barrier_arm = "v7", feature = "stdarch", feature = "nightly"))]
+#[inline(always)]
+pub fn dsb(domain: crate::arm::Domain, ty: BarrierType) {
+    use core::arch::arm::{__dsb, ISH, ISHST, NSH, NSHST, OSH, OSHST, ST, SY};
+
+    use crate::arm::Domain;
+
+    // SAFETY: This is just a data synchronization barrier.
+    unsafe {
+        match (domain, ty) {
+            (Domain::NonShareable, BarrierType::Write) => __dsb(NSHST),
+            (Domain::NonShareable, _) => __dsb(NSH),
+            (Domain::InnerShareable, BarrierType::Write) => __dsb(ISHST),
+            (Domain::InnerShareable, _) => __dsb(ISH),
+            (Domain::OuterShareable, BarrierType::Write) => __dsb(OSHST),
+            (Domain::OuterShareable, _) => __dsb(OSH),
+            (Domain::FullSystem, BarrierType::Write) => __dsb(ST),
+            (Domain::FullSystem, _) => __dsb(SY),
+        }
+    }
+}
+
+#[cfg(all(
+    mem_barrier_arm = "v7",
+    not(all(feature = "stdarch", feature = "nightly"))
+))]
+#[inline(always)]
+pub fn dsb(domain: crate::arm::Domain, ty: BarrierType) {
+    use crate::arm::Domain;
+
+    // SAFETY: This is just a data synchronization barrier.
+    unsafe {
+        match (domain, ty) {
+            (Domain::NonShareable, BarrierType::Write) => {
+                core::arch::asm!("dsb nshst", options(preserves_flags, nostack));
+            }
+            (Domain::NonShareable, _) => {
+                core::arch::asm!("dsb nsh", options(preserves_flags, nostack));
+            }
+            (Domain::InnerShareable, BarrierType::Write) => {
+                core::arch::asm!("dsb ishst", options(preserves_flags, nostack));
+            }
+            (Domain::InnerShareable, _) => {
+                core::arch::asm!("dsb ish", options(preserves_flags, nostack));
+            }
+            (Domain::OuterShareable, BarrierType::Write) => {
+                core::arch::asm!("dsb oshst", options(preserves_flags, nostack));
+            }
+            (Domain::OuterShareable, _) => {
+                core::arch::asm!("dsb osh", options(preserves_flags, nostack));
+            }
+            (Domain::FullSystem, BarrierType::Write) => {
+                core::arch::asm!("dsb st", options(preserves_flags, nostack));
+            }
+            (Domain::FullSystem, _) => {
+                core::arch::asm!("dsb sy", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
+
+// ARMv6 flushes the prefetch buffer via CP15, while ARMv7 and M-profile cores have a dedicated `isb` instruction.
+// ARMv5 and earlier have neither, so we only prevent compiler reordering.
+
+#[cfg(mem_barrier_arm = "v5")]
+#[inline(always)]
+pub fn instruction_barrier_asm() {
+    crate::compiler_barrier();
+}
+
+#[cfg(all(
+    any(
+        all(mem_barrier_arm = "v6", not(mem_barrier_arm_thumb1)),
+        mem_barrier_arm = "v6m",
+        mem_barrier_arm = "v7m",
+        mem_barrier_arm = "v7"
+    ),
+    feature = "stdarch",
+    feature = "nightly"
+))]
+#[inline(always)]
+pub fn instruction_barrier() {
+    use core::arch::arm::{__isb, SY};
+
+    // SAFETY: This is just an instruction synchronization barrier.
+    unsafe { __isb(SY) }
+}
+
+#[cfg(mem_barrier_arm = "v6")]
+#[inline(always)]
+pub fn instruction_barrier_asm() {
+    cp15::flush_prefetch_buffer();
+}
+
+#[cfg(any(
+    mem_barrier_arm = "v6m",
+    mem_barrier_arm = "v7m",
+    mem_barrier_arm = "v7"
+))]
+#[inline(always)]
+pub fn instruction_barrier_asm() {
+    // SAFETY: This is just an instruction synchronization barrier.
+    unsafe {
+        core::arch::asm!("isb", options(preserves_flags, nostack));
+    }
+}
+
+// Thumb-1 cannot encode coprocessor instructions, so the CP15 operations are always assembled as A32 code.
+// Calls to them switch to the ARM state, which prevents inlining into Thumb code.
+
+#[cfg(any(mem_barrier_arm = "v5", mem_barrier_arm = "v6"))]
+mod cp15 {
+    /// Drains the write buffer, which is a data synchronization barrier on ARMv6.
+    #[cfg_attr(mem_barrier_arm_thumb1, instruction_set(arm::a32), inline(never))]
+    #[cfg_attr(not(mem_barrier_arm_thumb1), inline(always))]
+    pub fn data_synchronization_barrier() {
+        // SAFETY: This just drains the write buffer.
+        unsafe {
+            core::arch::asm!(
+                "mcr p15, 0, {}, c7, c10, 4",
+                in(reg) 0_u32,
+                options(preserves_flags, nostack)
+            );
+        }
+    }
+
+    /// Issues a data memory barrier.
+    #[cfg(mem_barrier_arm = "v6")]
+    #[cfg_attr(mem_barrier_arm_thumb1, instruction_set(arm::a32), inline(never))]
+    #[cfg_attr(not(mem_barrier_arm_thumb1), inline(always))]
+    pub fn data_memory_barrier() {
+        // SAFETY: This is just a data memory barrier.
+        unsafe {
+            core::arch::asm!(
+                "mcr p15, 0, {}, c7, c10, 5",
+                in(reg) 0_u32,
+                options(preserves_flags, nostack)
+            );
+        }
+    }
+
+    /// Flushes the prefetch buffer, which is an instruction synchronization barrier.
+    #[cfg(mem_barrier_arm = "v6")]
+    #[cfg_attr(mem_barrier_arm_thumb1, instruction_set(arm::a32), inline(never))]
+    #[cfg_attr(not(mem_barrier_arm_thumb1), inline(always))]
+    pub fn flush_prefetch_buffer() {
+        // SAFETY: This just flushes the prefetch buffer.
+        unsafe {
+            core::arch::asm!(
+                "mcr p15, 0, {}, c7, c5, 4",
+                in(reg) 0_u32,
+                options(preserves_flags, nostack)
+            );
+        }
+    }
+}
+
+#[cfg(mem_barrier_arm = "v5")]
+pub const fn mnemonic(kind: CpuBarrierKind, _ty: BarrierType) -> &'static str {
+    match kind {
+        CpuBarrierKind::Mmio | CpuBarrierKind::Dma => "mcr p15, 0, <Rt>, c7, c10, 4",
+        CpuBarrierKind::Smp => "",
+    }
+}
+
+#[cfg(mem_barrier_arm = "v6")]
+pub const fn mnemonic(kind: CpuBarrierKind, _ty: BarrierType) -> &'static str {
+    match kind {
+        CpuBarrierKind::Mmio => "mcr p15, 0, <Rt>, c7, c10, 4",
+        CpuBarrierKind::Smp | CpuBarrierKind::Dma => "mcr p15, 0, <Rt>, c7, c10, 5",
+    }
+}
+
+#[cfg(any(mem_barrier_arm = "v6m", mem_barrier_arm = "v7m"))]
+pub const fn mnemonic(kind: CpuBarrierKind, _ty: BarrierType) -> &'static str {
+    match kind {
+        CpuBarrierKind::Mmio => "dsb",
+        CpuBarrierKind::Smp | CpuBarrierKind::Dma => "dmb",
+    }
+}
+
+#[cfg(mem_barrier_arm = "v7")]
+pub const fn mnemonic(kind: CpuBarrierKind, ty: BarrierType) -> &'static str {
+    match (kind, ty) {
+        (CpuBarrierKind::Mmio, BarrierType::Write) => "dsb st",
+        (CpuBarrierKind::Mmio, _) => "dsb sy",
+        (CpuBarrierKind::Smp, BarrierType::Write) => "dmb ishst",
+        (CpuBarrierKind::Smp, _) => "dmb ish",
+        (CpuBarrierKind::Dma, BarrierType::Write) => "dmb oshst",
+        (CpuBarrierKind::Dma, _) => "dmb osh",
+    }
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    cfg_if::cfg_if! {
+        if #[cfg(mem_barrier_arm = "v5")] {
+            ""
+        } else if #[cfg(mem_barrier_arm = "v6")] {
+            "mcr p15, 0, <Rt>, c7, c5, 4"
+        } else {
+            "isb"
+        }
+    }
+}
+
+pub const USES_STDARCH: bool = cfg!(all(
+    not(mem_barrier_arm = "v5"),
+    not(mem_barrier_arm_thumb1),
+    feature = "stdarch",
+    feature = "nightly"
+));
+
+pub const USES_NIGHTLY_INTRINSICS: bool = USES_STDARCH;
@@ -0,0 +1,30 @@
+use super::CpuBarrierKind;
+use crate::BarrierType;
+
+// AVR cores are uniprocessor and execute all memory accesses in program order, including I/O accesses.
+// They have no caches or write buffers, so devices observe accesses in program order as well.
+// Thus, all barriers are compiler barriers only, which is sufficient, not a placeholder.
+
+#[inline(always)]
+pub fn mem_barrier(_kind: CpuBarrierKind, _ty: BarrierType) {
+    crate::compiler_barrier();
+}
+
+// Program memory is only writable via `spm`, which halts the CPU until the write completes.
+
+#[inline(always)]
+pub fn instruction_barrier() {
+    crate::compiler_barrier();
+}
+
+pub const fn mnemonic(_kind: CpuBarrierKind, _ty: BarrierType) -> &'static str {
+    ""
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    ""
+}
+
+pub const USES_STDARCH: bool = false;
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
@@ -0,0 +1,48 @@
+use super::CpuBarrierKind;
+use crate::BarrierType;
+
+// BPF has no barrier instructions, so all barriers are compiler barriers only.
+// BPF programs that share memory between CPUs, such as maps, must use atomic instructions to order those accesses.
+//
+// LLVM cannot compile compiler fences or atomic fences for BPF.
+// Empty inline assembly is a compiler barrier, but inline assembly is unstable on BPF.
+
+#[cfg(not(mem_barrier_asm))]
+compile_error!("mem-barrier requires the `nightly` feature on BPF.");
+
+#[inline(always)]
+pub fn mem_barrier(_kind: CpuBarrierKind, _ty: BarrierType) {
+    compiler_barrier();
+}
+
+#[inline(always)]
+pub fn compiler_barrier() {
+    #[cfg(mem_barrier_asm)]
+    // SAFETY: This is empty.
+    unsafe {
+        core::arch::asm!("", options(preserves_flags, nostack));
+    }
+}
+
+// BPF programs cannot modify their own instructions.
+
+#[inline(always)]
+pub fn instruction_barrier() {
+    compiler_barrier();
+}
+
+pub const fn compiler_barrier_mnemonic() -> &'static str {
+    ""
+}
+
+pub const fn mnemonic(_kind: CpuBarrierKind, _ty: BarrierType) -> &'static str {
+    ""
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    ""
+}
+
+pub const USES_STDARCH: bool = false;
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
@@ -0,0 +1,87 @@
+use super::CpuBarrierKind;
+use crate::BarrierType;
+
+// `sync` waits until all earlier memory accesses have completed on the bus, which orders device accesses.
+// `sync.s` additionally makes them visible to other cores in the shareable domain.
+// `sync.i` additionally flushes the pipeline, so later instructions are fetched again.
+// C-SKY does not distinguish between reads and writes, so read and write barriers are full barriers.
+// LLVM spells these instructions with a `32` suffix, such as `sync32.s`.
+//
+// Inline assembly is unstable on C-SKY.
+// Without the `nightly` feature, we use a sequentially consistent atomic fence, which compiles to a `__sync_synchronize` call.
+
+#[cfg(not(mem_barrier_asm))]
+#[inline(always)]
+pub fn mem_barrier(_kind: CpuBarrierKind, _ty: BarrierType) {
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(mem_barrier_asm)]
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, _ty: BarrierType) {
+    // SAFETY: This is just a memory barrier.
+    unsafe {
+        match kind {
+            CpuBarrierKind::Mmio => core::arch::asm!("sync32", options(preserves_flags, nostack)),
+            CpuBarrierKind::Smp | CpuBarrierKind::Dma => {
+                core::arch::asm!("sync32.s", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
+
+// LLVM compiles `core::sync::atomic::compiler_fence` to a `__sync_synchronize` call on C-SKY.
+// Empty inline assembly is a compiler barrier without that call.
+
+#[cfg(not(mem_barrier_asm))]
+#[inline(always)]
+pub fn compiler_barrier() {
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(mem_barrier_asm)]
+#[inline(always)]
+pub fn compiler_barrier() {
+    // SAFETY: This is empty.
+    unsafe {
+        core::arch::asm!("", options(preserves_flags, nostack));
+    }
+}
+
+#[inline(always)]
+pub fn instruction_barrier() {
+    cfg_if::cfg_if! {
+        if #[cfg(mem_barrier_asm)] {
+            // SAFETY: This is just an instruction synchronization barrier.
+            unsafe {
+                core::arch::asm!("sync32.i", options(preserves_flags, nostack));
+            }
+        } else {
+            super::fallback::instruction_barrier();
+        }
+    }
+}
+
+pub const fn mnemonic(kind: CpuBarrierKind, _ty: BarrierType) -> &'static str {
+    match kind {
+        _ if !cfg!(mem_barrier_asm) => "fence",
+        CpuBarrierKind::Mmio => "sync32",
+        CpuBarrierKind::Smp | CpuBarrierKind::Dma => "sync32.s",
+    }
+}
+
+pub const fn compiler_barrier_mnemonic() -> &'static str {
+    if cfg!(mem_barrier_asm) { "" } else { "fence" }
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    if cfg!(mem_barrier_asm) {
+        "sync32.i"
+    } else {
+        "fence"
+    }
+}
+
+pub const USES_STDARCH: bool = false;
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
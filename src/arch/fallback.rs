@@ -0,0 +1,44 @@
+use core::sync::atomic::Ordering;
+
+use super::CpuBarrierKind;
+use crate::BarrierType;
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "loom")] {
+        pub use loom::sync::atomic::fence;
+    } else if #[cfg(feature = "portable-atomic")] {
+        pub use portable_atomic::fence;
+    } else {
+        pub use core::sync::atomic::fence;
+    }
+}
+
+// This is a conservative, portable fallback for architectures without dedicated support.
+// Atomic fences only order memory accesses with respect to other atomic operations in the Rust memory model.
+// In practice, compilers implement them using the architecture's barrier instructions, though.
+
+#[inline(always)]
+pub fn mem_barrier(_kind: CpuBarrierKind, ty: BarrierType) {
+    match ty {
+        BarrierType::General => fence(Ordering::SeqCst),
+        BarrierType::Read | BarrierType::Acquire => fence(Ordering::Acquire),
+        BarrierType::Write | BarrierType::Release => fence(Ordering::Release),
+    }
+}
+
+#[inline(always)]
+pub fn instruction_barrier() {
+    fence(Ordering::SeqCst);
+}
+
+pub const fn mnemonic(_kind: CpuBarrierKind, _ty: BarrierType) -> &'static str {
+    "fence"
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    "fence"
+}
+
+pub const USES_STDARCH: bool = false;
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
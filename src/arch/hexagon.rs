@@ -0,0 +1,84 @@
+use super::CpuBarrierKind;
+use crate::BarrierType;
+
+// `barrier` orders all earlier memory accesses before all later memory accesses as observed by other threads.
+// `syncht` additionally waits until all earlier transactions, including uncached device accesses, have completed.
+// Hexagon does not distinguish between reads and writes, so read and write barriers are full barriers.
+//
+// Inline assembly is unstable on Hexagon.
+// Without the `nightly` feature, we use a sequentially consistent atomic fence, which compiles to `barrier`.
+
+#[cfg(not(mem_barrier_asm))]
+#[inline(always)]
+pub fn mem_barrier(_kind: CpuBarrierKind, _ty: BarrierType) {
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(mem_barrier_asm)]
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, _ty: BarrierType) {
+    // SAFETY: This is just a memory barrier.
+    unsafe {
+        match kind {
+            CpuBarrierKind::Mmio => core::arch::asm!("syncht", options(preserves_flags, nostack)),
+            CpuBarrierKind::Smp | CpuBarrierKind::Dma => {
+                core::arch::asm!("barrier", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
+
+// LLVM compiles `core::sync::atomic::compiler_fence` to `barrier` on Hexagon.
+// Empty inline assembly is a compiler barrier without that instruction.
+
+#[cfg(not(mem_barrier_asm))]
+#[inline(always)]
+pub fn compiler_barrier() {
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(mem_barrier_asm)]
+#[inline(always)]
+pub fn compiler_barrier() {
+    // SAFETY: This is empty.
+    unsafe {
+        core::arch::asm!("", options(preserves_flags, nostack));
+    }
+}
+
+#[inline(always)]
+pub fn instruction_barrier() {
+    cfg_if::cfg_if! {
+        if #[cfg(mem_barrier_asm)] {
+            // SAFETY: This is just an instruction synchronization barrier.
+            unsafe {
+                core::arch::asm!("isync", options(preserves_flags, nostack));
+            }
+        } else {
+            super::fallback::instruction_barrier();
+        }
+    }
+}
+
+pub const fn mnemonic(kind: CpuBarrierKind, _ty: BarrierType) -> &'static str {
+    match kind {
+        CpuBarrierKind::Mmio if cfg!(mem_barrier_asm) => "syncht",
+        CpuBarrierKind::Mmio | CpuBarrierKind::Smp | CpuBarrierKind::Dma => "barrier",
+    }
+}
+
+pub const fn compiler_barrier_mnemonic() -> &'static str {
+    if cfg!(mem_barrier_asm) { "" } else { "barrier" }
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    if cfg!(mem_barrier_asm) {
+        "isync"
+    } else {
+        "barrier"
+    }
+}
+
+pub const USES_STDARCH: bool = false;
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
@@ -0,0 +1,89 @@
+use super::CpuBarrierKind;
+use crate::BarrierType;
+
+// `dbar 0` is the full completion barrier.
+// The hint bits restrict the barrier, like in the Linux barriers:
+//
+// - Bit 4 selects an ordering barrier instead of a completion barrier.
+// - Bit 3 excludes earlier loads.
+// - Bit 2 excludes earlier stores.
+// - Bit 1 excludes later loads.
+// - Bit 0 excludes later stores.
+//
+// Read and write barriers use the hints `0x5` (read-read) and `0xa` (write-write).
+// Acquire and release barriers use the hints `0x4` (read-read/write) and `0x2` (read/write-write).
+// SMP barriers only need to order accesses as observed by other CPUs, so they set bit 4.
+// MMIO and DMA barriers need the accesses to complete, so they use completion barriers.
+//
+// The hints are backward compatible: cores that do not implement a hint treat it as `dbar 0`.
+// There is no target feature for them, so they are always used.
+
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, ty: BarrierType) {
+    // SAFETY: This is just a data barrier.
+    unsafe {
+        match (kind, ty) {
+            (CpuBarrierKind::Smp, BarrierType::General) => {
+                core::arch::asm!("dbar 0x10", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::Smp, BarrierType::Read) => {
+                core::arch::asm!("dbar 0x15", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::Smp, BarrierType::Write) => {
+                core::arch::asm!("dbar 0x1a", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::Smp, BarrierType::Acquire) => {
+                core::arch::asm!("dbar 0x14", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::Smp, BarrierType::Release) => {
+                core::arch::asm!("dbar 0x12", options(preserves_flags, nostack));
+            }
+            (_, BarrierType::General) => {
+                core::arch::asm!("dbar 0", options(preserves_flags, nostack));
+            }
+            (_, BarrierType::Read) => {
+                core::arch::asm!("dbar 0x5", options(preserves_flags, nostack));
+            }
+            (_, BarrierType::Write) => {
+                core::arch::asm!("dbar 0xa", options(preserves_flags, nostack));
+            }
+            (_, BarrierType::Acquire) => {
+                core::arch::asm!("dbar 0x4", options(preserves_flags, nostack));
+            }
+            (_, BarrierType::Release) => {
+                core::arch::asm!("dbar 0x2", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
+
+#[inline(always)]
+pub fn instruction_barrier() {
+    // SAFETY: This is just an instruction barrier.
+    unsafe {
+        core::arch::asm!("ibar 0", options(preserves_flags, nostack));
+    }
+}
+
+pub const fn mnemonic(kind: CpuBarrierKind, ty: BarrierType) -> &'static str {
+    match (kind, ty) {
+        (CpuBarrierKind::Smp, BarrierType::General) => "dbar 0x10",
+        (CpuBarrierKind::Smp, BarrierType::Read) => "dbar 0x15",
+        (CpuBarrierKind::Smp, BarrierType::Write) => "dbar 0x1a",
+        (CpuBarrierKind::Smp, BarrierType::Acquire) => "dbar 0x14",
+        (CpuBarrierKind::Smp, BarrierType::Release) => "dbar 0x12",
+        (_, BarrierType::General) => "dbar 0",
+        (_, BarrierType::Read) => "dbar 0x5",
+        (_, BarrierType::Write) => "dbar 0xa",
+        (_, BarrierType::Acquire) => "dbar 0x4",
+        (_, BarrierType::Release) => "dbar 0x2",
+    }
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    "ibar 0"
+}
+
+pub const USES_STDARCH: bool = false;
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
@@ -0,0 +1,53 @@
+use super::CpuBarrierKind;
+use crate::BarrierType;
+
+// m68k systems are uniprocessor and strongly ordered, so SMP and DMA barriers are compiler barriers only.
+// For MMIO, `nop` waits for pending bus cycles to complete on pipelined cores such as the 68040 and 68060.
+//
+// Inline assembly is unstable on m68k.
+// Without the `nightly` feature, MMIO barriers are compiler barriers as well.
+
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, _ty: BarrierType) {
+    match kind {
+        #[cfg(mem_barrier_asm)]
+        CpuBarrierKind::Mmio => {
+            // SAFETY: This is just a pipeline synchronization.
+            unsafe {
+                core::arch::asm!("nop", options(preserves_flags, nostack));
+            }
+        }
+        #[cfg(not(mem_barrier_asm))]
+        CpuBarrierKind::Mmio => crate::compiler_barrier(),
+        CpuBarrierKind::Smp | CpuBarrierKind::Dma => crate::compiler_barrier(),
+    }
+}
+
+#[inline(always)]
+pub fn instruction_barrier() {
+    cfg_if::cfg_if! {
+        if #[cfg(mem_barrier_asm)] {
+            // SAFETY: This is just a pipeline synchronization.
+            unsafe {
+                core::arch::asm!("nop", options(preserves_flags, nostack));
+            }
+        } else {
+            crate::compiler_barrier();
+        }
+    }
+}
+
+pub const fn mnemonic(kind: CpuBarrierKind, _ty: BarrierType) -> &'static str {
+    match kind {
+        CpuBarrierKind::Mmio => instruction_barrier_mnemonic(),
+        CpuBarrierKind::Smp | CpuBarrierKind::Dma => "",
+    }
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    if cfg!(mem_barrier_asm) { "nop" } else { "" }
+}
+
+pub const USES_STDARCH: bool = false;
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
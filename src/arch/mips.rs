@@ -0,0 +1,105 @@
+use super::CpuBarrierKind;
+use crate::BarrierType;
+
+// Before Release 6, we only rely on the full completion barrier `sync`.
+// Release 6 also defines the lightweight ordering barriers `sync 0x10` (general), `sync 0x13` (read), `sync 0x4` (write), `sync 0x11` (acquire), and `sync 0x12` (release).
+//
+// Inline assembly is unstable on MIPS.
+// Without the `nightly` feature, we use a sequentially consistent atomic fence, which compiles to `sync`.
+//
+// The barrier instructions take no register operands, so they are the same on big-endian and little-endian targets.
+
+#[cfg(not(mem_barrier_asm))]
+#[inline(always)]
+pub fn mem_barrier(_kind: CpuBarrierKind, _ty: BarrierType) {
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(all(
+    mem_barrier_asm,
+    not(any(target_arch = "mips32r6", target_arch = "mips64r6"))
+))]
+#[inline(always)]
+pub fn mem_barrier(_kind: CpuBarrierKind, _ty: BarrierType) {
+    // SAFETY: This is just a memory barrier.
+    unsafe {
+        core::arch::asm!("sync", options(preserves_flags, nostack));
+    }
+}
+
+#[cfg(all(
+    mem_barrier_asm,
+    any(target_arch = "mips32r6", target_arch = "mips64r6")
+))]
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, ty: BarrierType) {
+    // SAFETY: This is just a memory barrier.
+    unsafe {
+        match (kind, ty) {
+            (
+                CpuBarrierKind::Mmio,
+                BarrierType::General
+                | BarrierType::Read
+                | BarrierType::Write
+                | BarrierType::Acquire
+                | BarrierType::Release,
+            ) => {
+                core::arch::asm!("sync", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::General) => {
+                core::arch::asm!("sync 0x10", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::Read) => {
+                core::arch::asm!("sync 0x13", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::Write) => {
+                core::arch::asm!("sync 0x4", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::Acquire) => {
+                core::arch::asm!("sync 0x11", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::Release) => {
+                core::arch::asm!("sync 0x12", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
+
+// Clearing instruction hazards requires a `jr.hb` to a known address, which is not expressible without relocations.
+// Instead, we use a full completion barrier.
+
+#[inline(always)]
+pub fn instruction_barrier() {
+    super::fallback::instruction_barrier();
+}
+
+#[cfg(not(all(
+    mem_barrier_asm,
+    any(target_arch = "mips32r6", target_arch = "mips64r6")
+)))]
+pub const fn mnemonic(_kind: CpuBarrierKind, _ty: BarrierType) -> &'static str {
+    "sync"
+}
+
+#[cfg(all(
+    mem_barrier_asm,
+    any(target_arch = "mips32r6", target_arch = "mips64r6")
+))]
+pub const fn mnemonic(kind: CpuBarrierKind, ty: BarrierType) -> &'static str {
+    match (kind, ty) {
+        (CpuBarrierKind::Mmio, _) => "sync",
+        (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::General) => "sync 0x10",
+        (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::Read) => "sync 0x13",
+        (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::Write) => "sync 0x4",
+        (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::Acquire) => "sync 0x11",
+        (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::Release) => "sync 0x12",
+    }
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    "sync"
+}
+
+pub const USES_STDARCH: bool = false;
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
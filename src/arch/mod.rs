@@ -1,18 +1,167 @@
+// Under Miri and loom, the fallback shadows the implementations of the architecture modules and their re-exports.
+#![cfg_attr(any(miri, feature = "loom"), allow(dead_code, unused_imports))]
+
+use crate::BarrierType;
+
 cfg_if::cfg_if! {
     if #[cfg(target_arch = "aarch64")] {
         mod aarch64;
         pub use self::aarch64::*;
+    } else if #[cfg(target_arch = "arm")] {
+        mod arm;
+        pub use self::arm::*;
+    } else if #[cfg(target_arch = "avr")] {
+        mod avr;
+        pub use self::avr::*;
+    } else if #[cfg(target_arch = "bpf")] {
+        mod bpf;
+        pub use self::bpf::*;
+    } else if #[cfg(target_arch = "csky")] {
+        mod csky;
+        pub use self::csky::*;
+    } else if #[cfg(target_arch = "hexagon")] {
+        mod hexagon;
+        pub use self::hexagon::*;
+    } else if #[cfg(target_arch = "loongarch64")] {
+        mod loongarch64;
+        pub use self::loongarch64::*;
+    } else if #[cfg(target_arch = "m68k")] {
+        mod m68k;
+        pub use self::m68k::*;
+    } else if #[cfg(any(
+        target_arch = "mips",
+        target_arch = "mips32r6",
+        target_arch = "mips64",
+        target_arch = "mips64r6"
+    ))] {
+        mod mips;
+        pub use self::mips::*;
+    } else if #[cfg(target_arch = "nvptx64")] {
+        mod nvptx64;
+        pub use self::nvptx64::*;
+    } else if #[cfg(any(target_arch = "powerpc", target_arch = "powerpc64"))] {
+        mod powerpc;
+        pub use self::powerpc::*;
     } else if #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))] {
         mod riscv;
         pub use self::riscv::*;
+    } else if #[cfg(target_arch = "s390x")] {
+        mod s390x;
+        pub use self::s390x::*;
+    } else if #[cfg(all(target_arch = "sparc", not(target_feature = "v9")))] {
+        mod sparc;
+        pub use self::sparc::*;
+    } else if #[cfg(any(target_arch = "sparc", target_arch = "sparc64"))] {
+        // 32-bit SPARC with V9 instructions, such as V8+, uses the same barriers as SPARC64.
+        mod sparc64;
+        pub use self::sparc64::*;
+    } else if #[cfg(any(target_arch = "wasm32", target_arch = "wasm64"))] {
+        mod wasm;
+        pub use self::wasm::*;
     } else if #[cfg(any(target_arch = "x86", target_arch = "x86_64"))] {
         mod x86;
         pub use self::x86::*;
+    } else if #[cfg(target_arch = "xtensa")] {
+        mod xtensa;
+        pub use self::xtensa::*;
+    } else if #[cfg(feature = "fallback")] {
+        pub use self::fallback::*;
+    } else {
+        compile_error!(concat!(
+            "mem-barrier does not support the `",
+            env!("MEM_BARRIER_TARGET_ARCH"),
+            "` architecture. Enable the `fallback` feature to use `core::sync::atomic::fence` instead."
+        ));
+
+        // This avoids follow-up errors.
+        pub use self::fallback::*;
+    }
+}
+
+// Miri cannot run inline assembly, and loom cannot reason about it, so we run atomic fences instead.
+// The mnemonics still describe the instructions of the target architecture.
+#[cfg(any(miri, feature = "loom"))]
+pub use self::fallback::{
+    USES_NIGHTLY_INTRINSICS, USES_STDARCH, instruction_barrier,
+    instruction_barrier as instruction_barrier_asm, mem_barrier, mem_barrier as mem_barrier_asm,
+};
+// Only these architectures have barrier implementations based on intrinsics, which their modules pair with an inline assembly implementation.
+// On other architectures, the only implementation doubles as the inline assembly implementation.
+#[cfg(not(any(
+    miri,
+    feature = "loom",
+    target_arch = "aarch64",
+    target_arch = "arm",
+    target_arch = "x86",
+    target_arch = "x86_64"
+)))]
+pub use self::{instruction_barrier as instruction_barrier_asm, mem_barrier as mem_barrier_asm};
+
+// Only RISC-V can order arbitrary sets of accesses, so other architectures round up to the nearest barrier type.
+#[cfg(any(
+    miri,
+    feature = "loom",
+    not(any(target_arch = "riscv32", target_arch = "riscv64"))
+))]
+#[inline(always)]
+pub fn mem_barrier_ordered(kind: CpuBarrierKind, ordering: crate::AccessOrdering) {
+    mem_barrier(kind, ordering.barrier_type());
+}
+
+// On these architectures, LLVM either does not support compiler fences or compiles them to CPU instructions.
+// Their modules provide a compiler barrier based on empty inline assembly instead.
+cfg_if::cfg_if! {
+    if #[cfg(any(
+        target_arch = "bpf",
+        target_arch = "csky",
+        target_arch = "hexagon",
+        target_arch = "nvptx64",
+        target_arch = "sparc",
+        target_arch = "sparc64"
+    ))] {
+        #[inline(always)]
+        pub fn compiler_fence(_ty: BarrierType) {
+            compiler_barrier();
+        }
+    } else {
+        #[inline(always)]
+        pub fn compiler_barrier() {
+            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+        }
+
+        pub const fn compiler_barrier_mnemonic() -> &'static str {
+            ""
+        }
+
+        #[inline(always)]
+        pub fn compiler_fence(ty: BarrierType) {
+            use core::sync::atomic::Ordering;
+
+            let order = match ty {
+                BarrierType::General => Ordering::SeqCst,
+                BarrierType::Read | BarrierType::Acquire => Ordering::Acquire,
+                BarrierType::Write | BarrierType::Release => Ordering::Release,
+            };
+            core::sync::atomic::compiler_fence(order);
+        }
     }
 }
 
+// The fallback is always compiled to be able to test it on any architecture.
+#[cfg_attr(not(test), allow(dead_code))]
+pub(crate) mod fallback;
+
+#[derive(Clone, Copy)]
 pub enum CpuBarrierKind {
     Mmio,
     Smp,
     Dma,
+    #[cfg(target_arch = "aarch64")]
+    DmaCompletion,
+    #[cfg(target_arch = "aarch64")]
+    System,
+    #[cfg(target_arch = "aarch64")]
+    NonShareable,
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    Device,
 }
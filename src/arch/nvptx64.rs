@@ -0,0 +1,79 @@
+use super::CpuBarrierKind;
+use crate::BarrierType;
+
+// Support for NVPTX is best-effort and intended for sharing code between the host and the GPU.
+//
+// `membar.gl` orders memory accesses as observed by all threads on the GPU, which corresponds to SMP barriers.
+// `membar.sys` additionally orders them as observed by the host and other devices, which MMIO and DMA barriers require.
+// PTX does not distinguish between reads and writes, so read and write barriers are full barriers.
+// Thread-block synchronization with `bar.sync` is an execution barrier, not a memory barrier, so it is out of scope.
+//
+// Inline assembly is unstable on NVPTX.
+// Without the `nightly` feature, we use a sequentially consistent atomic fence, which compiles to a system-scope fence.
+
+#[cfg(not(mem_barrier_asm))]
+#[inline(always)]
+pub fn mem_barrier(_kind: CpuBarrierKind, _ty: BarrierType) {
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(mem_barrier_asm)]
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, _ty: BarrierType) {
+    // SAFETY: This is just a memory barrier.
+    unsafe {
+        match kind {
+            CpuBarrierKind::Mmio | CpuBarrierKind::Dma => {
+                core::arch::asm!("membar.sys;", options(preserves_flags, nostack));
+            }
+            CpuBarrierKind::Smp => {
+                core::arch::asm!("membar.gl;", options(preserves_flags, nostack))
+            }
+        }
+    }
+}
+
+// LLVM does not support single-thread fences on NVPTX, so `core::sync::atomic::compiler_fence` fails to compile.
+// Empty inline assembly is a compiler barrier as well.
+
+#[cfg(not(mem_barrier_asm))]
+#[inline(always)]
+pub fn compiler_barrier() {
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(mem_barrier_asm)]
+#[inline(always)]
+pub fn compiler_barrier() {
+    // SAFETY: This is empty.
+    unsafe {
+        core::arch::asm!("", options(preserves_flags, nostack));
+    }
+}
+
+// PTX has no instruction to synchronize instruction fetches, so we run the strongest memory barrier instead.
+
+#[inline(always)]
+pub fn instruction_barrier() {
+    mem_barrier(CpuBarrierKind::Mmio, BarrierType::General);
+}
+
+pub const fn mnemonic(kind: CpuBarrierKind, _ty: BarrierType) -> &'static str {
+    match kind {
+        _ if !cfg!(mem_barrier_asm) => "fence",
+        CpuBarrierKind::Mmio | CpuBarrierKind::Dma => "membar.sys",
+        CpuBarrierKind::Smp => "membar.gl",
+    }
+}
+
+pub const fn compiler_barrier_mnemonic() -> &'static str {
+    if cfg!(mem_barrier_asm) { "" } else { "fence" }
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    mnemonic(CpuBarrierKind::Mmio, BarrierType::General)
+}
+
+pub const USES_STDARCH: bool = false;
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
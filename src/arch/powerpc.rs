@@ -0,0 +1,84 @@
+use super::CpuBarrierKind;
+use crate::BarrierType;
+
+// `lwsync` does not order earlier stores against later loads, so general barriers need `sync`.
+// It orders all other combinations of loads and stores, which makes it suitable for acquire and release barriers.
+// Some cores, such as the e500, do not implement `lwsync` and fall back to `sync` instead.
+// `eieio` orders stores to both device and system memory, which makes it suitable for DMA write barriers.
+//
+// The barrier instructions take no register operands, so they are the same on big-endian and little-endian targets.
+
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, ty: BarrierType) {
+    // SAFETY: This is just a memory barrier.
+    unsafe {
+        match (kind, ty) {
+            (
+                CpuBarrierKind::Mmio,
+                BarrierType::General
+                | BarrierType::Read
+                | BarrierType::Write
+                | BarrierType::Acquire
+                | BarrierType::Release,
+            ) => {
+                core::arch::asm!("sync", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::General) => {
+                core::arch::asm!("sync", options(preserves_flags, nostack));
+            }
+            #[cfg(not(mem_barrier_no_lwsync))]
+            (
+                CpuBarrierKind::Smp | CpuBarrierKind::Dma,
+                BarrierType::Read | BarrierType::Acquire | BarrierType::Release,
+            )
+            | (CpuBarrierKind::Smp, BarrierType::Write) => {
+                core::arch::asm!("lwsync", options(preserves_flags, nostack));
+            }
+            #[cfg(mem_barrier_no_lwsync)]
+            (
+                CpuBarrierKind::Smp | CpuBarrierKind::Dma,
+                BarrierType::Read | BarrierType::Acquire | BarrierType::Release,
+            )
+            | (CpuBarrierKind::Smp, BarrierType::Write) => {
+                core::arch::asm!("sync", options(preserves_flags, nostack));
+            }
+            (CpuBarrierKind::Dma, BarrierType::Write) => {
+                core::arch::asm!("eieio", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
+
+// `isync` discards prefetched instructions, but does not order data accesses.
+// After modifying code, it follows `sync`, which the caller runs as an MMIO barrier.
+
+#[inline(always)]
+pub fn instruction_barrier() {
+    // SAFETY: This is just an instruction synchronization.
+    unsafe {
+        core::arch::asm!("isync", options(preserves_flags, nostack));
+    }
+}
+
+pub const fn mnemonic(kind: CpuBarrierKind, ty: BarrierType) -> &'static str {
+    match (kind, ty) {
+        (CpuBarrierKind::Mmio, _)
+        | (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::General) => "sync",
+        (CpuBarrierKind::Dma, BarrierType::Write) => "eieio",
+        _ => {
+            if cfg!(mem_barrier_no_lwsync) {
+                "sync"
+            } else {
+                "lwsync"
+            }
+        }
+    }
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    "isync"
+}
+
+pub const USES_STDARCH: bool = false;
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
@@ -1,29 +1,99 @@
 use super::CpuBarrierKind;
-use crate::BarrierType;
+use crate::riscv::{FenceSet, fence};
+use crate::{AccessOrdering, AccessSet, BarrierType};
 
-#[inline]
+// Memory reads and writes map to the fence sets `r` and `w`.
+// DMA only accesses main memory, so only MMIO barriers extend them to device input and output.
+// Device barriers only order device I/O.
+// With the Ztso extension, SMP barriers that RVTSO already enforces are compiler barriers only.
+
+#[inline(always)]
 pub fn mem_barrier(kind: CpuBarrierKind, ty: BarrierType) {
-    // SAFETY: This is just a memory ordering fence.
+    mem_barrier_ordered(kind, ty.ordering());
+}
+
+#[inline(always)]
+pub fn mem_barrier_ordered(kind: CpuBarrierKind, ordering: AccessOrdering) {
+    if is_tso(kind, ordering) {
+        crate::compiler_barrier();
+        return;
+    }
+
+    fence(
+        fence_set(kind, ordering.before),
+        fence_set(kind, ordering.after),
+    );
+}
+
+/// Returns `true` if the Ztso extension already enforces the ordering for this kind of barrier.
+///
+/// RVTSO, like x86, only lets later loads be reordered before earlier stores.
+/// It does not cover device I/O, so this only applies to SMP barriers.
+#[inline(always)]
+const fn is_tso(kind: CpuBarrierKind, ordering: AccessOrdering) -> bool {
+    let AccessOrdering { before, after } = ordering;
+    cfg!(target_feature = "ztso")
+        && matches!(kind, CpuBarrierKind::Smp)
+        && !before.is_empty()
+        && !after.is_empty()
+        && !(before.contains(AccessSet::WRITE) && after.contains(AccessSet::READ))
+}
+
+/// Returns the fence operations that order the memory accesses for this kind of barrier.
+#[inline(always)]
+const fn fence_set(kind: CpuBarrierKind, accesses: AccessSet) -> FenceSet {
+    let (read, write) = match kind {
+        CpuBarrierKind::Mmio => (
+            FenceSet::I.union(FenceSet::R),
+            FenceSet::O.union(FenceSet::W),
+        ),
+        CpuBarrierKind::Smp | CpuBarrierKind::Dma => (FenceSet::R, FenceSet::W),
+        CpuBarrierKind::Device => (FenceSet::I, FenceSet::O),
+    };
+
+    let mut set = FenceSet::empty();
+    if accesses.contains(AccessSet::READ) {
+        set = set.union(read);
+    }
+    if accesses.contains(AccessSet::WRITE) {
+        set = set.union(write);
+    }
+    set
+}
+
+#[inline(always)]
+pub fn instruction_barrier() {
+    // SAFETY: This is just an instruction fetch fence.
     unsafe {
-        match (kind, ty) {
-            (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, BarrierType::General) => {
-                core::arch::asm!("fence iorw, iorw", options(preserves_flags, nostack));
-            }
-            (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, BarrierType::Read) => {
-                core::arch::asm!("fence ir, ir", options(preserves_flags, nostack));
-            }
-            (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, BarrierType::Write) => {
-                core::arch::asm!("fence ow, ow", options(preserves_flags, nostack));
-            }
-            (CpuBarrierKind::Smp, BarrierType::General) => {
-                core::arch::asm!("fence rw, rw", options(preserves_flags, nostack));
-            }
-            (CpuBarrierKind::Smp, BarrierType::Read) => {
-                core::arch::asm!("fence r, r", options(preserves_flags, nostack));
-            }
-            (CpuBarrierKind::Smp, BarrierType::Write) => {
-                core::arch::asm!("fence w, w", options(preserves_flags, nostack));
-            }
-        }
+        core::arch::asm!("fence.i", options(preserves_flags, nostack));
+    }
+}
+
+pub const fn mnemonic(kind: CpuBarrierKind, ty: BarrierType) -> &'static str {
+    match (kind, ty) {
+        _ if is_tso(kind, ty.ordering()) => "",
+        (CpuBarrierKind::Mmio, BarrierType::General) => "fence iorw, iorw",
+        (CpuBarrierKind::Mmio, BarrierType::Read) => "fence ir, ir",
+        (CpuBarrierKind::Mmio, BarrierType::Write) => "fence ow, ow",
+        (CpuBarrierKind::Mmio, BarrierType::Acquire) => "fence ir, iorw",
+        (CpuBarrierKind::Mmio, BarrierType::Release) => "fence iorw, ow",
+        (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::General) => "fence rw, rw",
+        (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::Read) => "fence r, r",
+        (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::Write) => "fence w, w",
+        (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::Acquire) => "fence r, rw",
+        (CpuBarrierKind::Smp | CpuBarrierKind::Dma, BarrierType::Release) => "fence rw, w",
+        (CpuBarrierKind::Device, BarrierType::General) => "fence io, io",
+        (CpuBarrierKind::Device, BarrierType::Read) => "fence i, i",
+        (CpuBarrierKind::Device, BarrierType::Write) => "fence o, o",
+        (CpuBarrierKind::Device, BarrierType::Acquire) => "fence i, io",
+        (CpuBarrierKind::Device, BarrierType::Release) => "fence io, o",
     }
 }
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    "fence.i"
+}
+
+pub const USES_STDARCH: bool = false;
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
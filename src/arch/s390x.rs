@@ -0,0 +1,53 @@
+use super::CpuBarrierKind;
+use crate::BarrierType;
+
+// z/Architecture is strongly ordered, so read and write barriers are full serialization barriers.
+//
+// `bcr 15,0` performs full serialization including checkpoint synchronization.
+// The lighter `bcr 14,0` requires the fast-BCR-serialization facility.
+// Since there is no target feature for that facility, we use the high-word facility as a proxy, which was introduced alongside it with z196.
+
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, _ty: BarrierType) {
+    // SAFETY: This is just a serialization barrier.
+    unsafe {
+        match kind {
+            #[cfg(target_feature = "high-word")]
+            CpuBarrierKind::Smp | CpuBarrierKind::Dma => {
+                core::arch::asm!("bcr 14,0", options(preserves_flags, nostack));
+            }
+            #[cfg(not(target_feature = "high-word"))]
+            CpuBarrierKind::Smp | CpuBarrierKind::Dma => {
+                core::arch::asm!("bcr 15,0", options(preserves_flags, nostack));
+            }
+            CpuBarrierKind::Mmio => {
+                core::arch::asm!("bcr 15,0", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
+
+#[inline(always)]
+pub fn instruction_barrier() {
+    // SAFETY: This is just a serialization barrier.
+    unsafe {
+        core::arch::asm!("bcr 15,0", options(preserves_flags, nostack));
+    }
+}
+
+pub const fn mnemonic(kind: CpuBarrierKind, _ty: BarrierType) -> &'static str {
+    match kind {
+        CpuBarrierKind::Smp | CpuBarrierKind::Dma if cfg!(target_feature = "high-word") => {
+            "bcr 14,0"
+        }
+        CpuBarrierKind::Mmio | CpuBarrierKind::Smp | CpuBarrierKind::Dma => "bcr 15,0",
+    }
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    "bcr 15,0"
+}
+
+pub const USES_STDARCH: bool = false;
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
@@ -0,0 +1,152 @@
+use super::CpuBarrierKind;
+use crate::BarrierType;
+
+// SPARC V8 processors run in total store order (TSO), so SMP read, write, acquire, and release barriers are compiler barriers only.
+// Loads are not reordered with later loads or stores, so MMIO and DMA read and acquire barriers are compiler barriers as well.
+// `stbar` orders earlier stores before later stores, which V8 processors in partial store order (PSO) require.
+// V8 has no `membar`, so general barriers additionally run an atomic `ldstub` on a stack slot, which drains the store buffer.
+//
+// Inline assembly is unstable on SPARC.
+// Without the `nightly` feature, we use a sequentially consistent atomic fence, which compiles to `stbar` followed by `ldstub`.
+
+#[cfg(not(mem_barrier_asm))]
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, ty: BarrierType) {
+    match (kind, ty) {
+        (
+            CpuBarrierKind::Smp,
+            BarrierType::Read | BarrierType::Write | BarrierType::Acquire | BarrierType::Release,
+        )
+        | (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, BarrierType::Read | BarrierType::Acquire) => {
+            compiler_barrier();
+        }
+        (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, _)
+        | (CpuBarrierKind::Smp, BarrierType::General) => {
+            core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+        }
+    }
+}
+
+#[cfg(mem_barrier_asm)]
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, ty: BarrierType) {
+    match (kind, ty) {
+        (
+            CpuBarrierKind::Mmio | CpuBarrierKind::Smp | CpuBarrierKind::Dma,
+            BarrierType::General,
+        ) => {
+            full_barrier();
+        }
+        (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, BarrierType::Write | BarrierType::Release) => {
+            // SAFETY: This is just a store barrier.
+            unsafe {
+                core::arch::asm!("stbar", options(preserves_flags, nostack));
+            }
+        }
+        (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, BarrierType::Read | BarrierType::Acquire)
+        | (
+            CpuBarrierKind::Smp,
+            BarrierType::Read | BarrierType::Write | BarrierType::Acquire | BarrierType::Release,
+        ) => {
+            compiler_barrier();
+        }
+    }
+}
+
+#[cfg(mem_barrier_asm)]
+#[inline(always)]
+fn full_barrier() {
+    let mut slot = 0_u8;
+
+    // SAFETY: This is just a store barrier followed by an atomic store to a valid address.
+    unsafe {
+        core::arch::asm!(
+            "stbar",
+            "ldstub [{}], %g0",
+            in(reg) &raw mut slot,
+            options(preserves_flags, nostack)
+        );
+    }
+}
+
+// LLVM compiles `core::sync::atomic::compiler_fence` to `stbar` and `ldstub` on SPARC.
+// Empty inline assembly is a compiler barrier without these instructions.
+
+#[cfg(not(mem_barrier_asm))]
+#[inline(always)]
+pub fn compiler_barrier() {
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(mem_barrier_asm)]
+#[inline(always)]
+pub fn compiler_barrier() {
+    // SAFETY: This is empty.
+    unsafe {
+        core::arch::asm!("", options(preserves_flags, nostack));
+    }
+}
+
+// `flush` synchronizes instruction fetches with earlier stores to the given address.
+// We pass the address of a stack slot, which is always mapped.
+
+#[cfg(not(mem_barrier_asm))]
+#[inline(always)]
+pub fn instruction_barrier() {
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(mem_barrier_asm)]
+#[inline(always)]
+pub fn instruction_barrier() {
+    let slot = 0_u32;
+
+    // SAFETY: This is just a pipeline flush for a valid address.
+    unsafe {
+        core::arch::asm!(
+            "flush {}",
+            in(reg) &slot,
+            options(preserves_flags, nostack)
+        );
+    }
+}
+
+pub const fn mnemonic(kind: CpuBarrierKind, ty: BarrierType) -> &'static str {
+    match (kind, ty) {
+        (
+            CpuBarrierKind::Smp,
+            BarrierType::Read | BarrierType::Write | BarrierType::Acquire | BarrierType::Release,
+        )
+        | (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, BarrierType::Read | BarrierType::Acquire) => {
+            compiler_barrier_mnemonic()
+        }
+        (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, BarrierType::Write | BarrierType::Release)
+            if cfg!(mem_barrier_asm) =>
+        {
+            "stbar"
+        }
+        (CpuBarrierKind::Mmio | CpuBarrierKind::Smp | CpuBarrierKind::Dma, _) => FULL_BARRIER,
+    }
+}
+
+pub const fn compiler_barrier_mnemonic() -> &'static str {
+    if cfg!(mem_barrier_asm) {
+        ""
+    } else {
+        FULL_BARRIER
+    }
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    if cfg!(mem_barrier_asm) {
+        "flush <Rt>"
+    } else {
+        FULL_BARRIER
+    }
+}
+
+const FULL_BARRIER: &str = "stbar; ldstub [<Rt>], %g0";
+
+pub const USES_STDARCH: bool = false;
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
@@ -0,0 +1,153 @@
+use super::CpuBarrierKind;
+use crate::BarrierType;
+
+// SPARC V9 processors run in total store order (TSO), so SMP read, write, acquire, and release barriers are compiler barriers only.
+//
+// Inline assembly is unstable on SPARC.
+// Without the `nightly` feature, we use a sequentially consistent atomic fence, which compiles to a full `membar`.
+
+#[cfg(not(mem_barrier_asm))]
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, ty: BarrierType) {
+    match (kind, ty) {
+        (
+            CpuBarrierKind::Smp,
+            BarrierType::Read | BarrierType::Write | BarrierType::Acquire | BarrierType::Release,
+        ) => crate::compiler_barrier(),
+        (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, _)
+        | (CpuBarrierKind::Smp, BarrierType::General) => {
+            core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+        }
+    }
+}
+
+#[cfg(mem_barrier_asm)]
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, ty: BarrierType) {
+    // SAFETY: This is just a memory barrier.
+    unsafe {
+        match (kind, ty) {
+            (
+                CpuBarrierKind::Mmio | CpuBarrierKind::Smp | CpuBarrierKind::Dma,
+                BarrierType::General,
+            ) => {
+                core::arch::asm!(
+                    "membar #LoadLoad | #LoadStore | #StoreStore | #StoreLoad",
+                    options(preserves_flags, nostack)
+                );
+            }
+            (
+                CpuBarrierKind::Mmio | CpuBarrierKind::Dma,
+                BarrierType::Read | BarrierType::Acquire,
+            ) => {
+                core::arch::asm!(
+                    "membar #LoadLoad | #LoadStore",
+                    options(preserves_flags, nostack)
+                );
+            }
+            (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, BarrierType::Write) => {
+                core::arch::asm!(
+                    "membar #StoreStore | #StoreLoad",
+                    options(preserves_flags, nostack)
+                );
+            }
+            (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, BarrierType::Release) => {
+                core::arch::asm!(
+                    "membar #LoadStore | #StoreStore",
+                    options(preserves_flags, nostack)
+                );
+            }
+            (
+                CpuBarrierKind::Smp,
+                BarrierType::Read
+                | BarrierType::Write
+                | BarrierType::Acquire
+                | BarrierType::Release,
+            ) => {
+                crate::compiler_barrier();
+            }
+        }
+    }
+}
+
+// LLVM compiles `core::sync::atomic::compiler_fence` to `membar` on SPARC.
+// Empty inline assembly is a compiler barrier without that instruction.
+
+#[cfg(not(mem_barrier_asm))]
+#[inline(always)]
+pub fn compiler_barrier() {
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(mem_barrier_asm)]
+#[inline(always)]
+pub fn compiler_barrier() {
+    // SAFETY: This is empty.
+    unsafe {
+        core::arch::asm!("", options(preserves_flags, nostack));
+    }
+}
+
+// `flush` synchronizes instruction fetches with earlier stores to the given address and flushes the pipeline.
+// We pass the address of a stack slot, which is always mapped.
+
+#[cfg(not(mem_barrier_asm))]
+#[inline(always)]
+pub fn instruction_barrier() {
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(mem_barrier_asm)]
+#[inline(always)]
+pub fn instruction_barrier() {
+    let slot = 0_u64;
+
+    // SAFETY: This is just a pipeline flush for a valid address.
+    unsafe {
+        core::arch::asm!(
+            "flush {}",
+            in(reg) &slot,
+            options(preserves_flags, nostack)
+        );
+    }
+}
+
+pub const fn mnemonic(kind: CpuBarrierKind, ty: BarrierType) -> &'static str {
+    match (kind, ty) {
+        (CpuBarrierKind::Smp, BarrierType::General) => FULL_MEMBAR,
+        (CpuBarrierKind::Smp, _) => compiler_barrier_mnemonic(),
+        (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, _) if !cfg!(mem_barrier_asm) => FULL_MEMBAR,
+        (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, BarrierType::General) => FULL_MEMBAR,
+        (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, BarrierType::Read | BarrierType::Acquire) => {
+            "membar #LoadLoad | #LoadStore"
+        }
+        (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, BarrierType::Write) => {
+            "membar #StoreStore | #StoreLoad"
+        }
+        (CpuBarrierKind::Mmio | CpuBarrierKind::Dma, BarrierType::Release) => {
+            "membar #LoadStore | #StoreStore"
+        }
+    }
+}
+
+pub const fn compiler_barrier_mnemonic() -> &'static str {
+    if cfg!(mem_barrier_asm) {
+        ""
+    } else {
+        FULL_MEMBAR
+    }
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    if cfg!(mem_barrier_asm) {
+        "flush <Rt>"
+    } else {
+        FULL_MEMBAR
+    }
+}
+
+const FULL_MEMBAR: &str = "membar #LoadLoad | #LoadStore | #StoreStore | #StoreLoad";
+
+pub const USES_STDARCH: bool = false;
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
@@ -0,0 +1,47 @@
+use super::CpuBarrierKind;
+use crate::BarrierType;
+
+// WebAssembly has no dedicated barrier instructions.
+// With the threads proposal, atomic fences compile to `atomic.fence`.
+// Without shared memory, there is no other agent that could observe reordering.
+
+#[cfg(target_feature = "atomics")]
+#[inline(always)]
+pub fn mem_barrier(_kind: CpuBarrierKind, ty: BarrierType) {
+    use core::sync::atomic::{Ordering, fence};
+
+    match ty {
+        BarrierType::General => fence(Ordering::SeqCst),
+        BarrierType::Read | BarrierType::Acquire => fence(Ordering::Acquire),
+        BarrierType::Write | BarrierType::Release => fence(Ordering::Release),
+    }
+}
+
+#[cfg(not(target_feature = "atomics"))]
+#[inline(always)]
+pub fn mem_barrier(_kind: CpuBarrierKind, _ty: BarrierType) {
+    crate::compiler_barrier();
+}
+
+// WebAssembly code cannot be modified at runtime, so there is no instruction stream to synchronize.
+
+#[inline(always)]
+pub fn instruction_barrier() {
+    crate::compiler_barrier();
+}
+
+pub const fn mnemonic(_kind: CpuBarrierKind, _ty: BarrierType) -> &'static str {
+    if cfg!(target_feature = "atomics") {
+        "atomic.fence"
+    } else {
+        ""
+    }
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    ""
+}
+
+pub const USES_STDARCH: bool = false;
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
@@ -1,9 +1,25 @@
 use super::CpuBarrierKind;
 use crate::BarrierType;
+use crate::x86::Features;
 
-#[cfg(feature = "stdarch")]
-#[inline]
-pub fn mem_barrier(_kind: CpuBarrierKind, ty: BarrierType) {
+// Before SSE2, x86 has no fence instructions.
+// Instead, we use a locked instruction, which orders all memory accesses.
+// The build script emits `mem_barrier_x86_fence` if the fence instructions are available.
+// `mem_barrier_with` lets callers opt into fences detected at runtime, since locked instructions do not order write-combining stores.
+//
+// x86 is strongly ordered (TSO) for normal memory, so SMP read, write, acquire, and release barriers are compiler barriers only.
+// Only SMP general barriers need a fence, since stores may be reordered after later loads.
+// MMIO and DMA barriers still need fences to order non-temporal stores and weakly ordered memory types.
+//
+// With the `x86-lock-fence` feature, SMP general barriers use a locked instruction as well, which is faster than `mfence` on many processors.
+// MMIO and DMA barriers never use a locked instruction, though.
+// Locked instructions are not guaranteed to order non-temporal stores or stores to write-combining memory, such as a mapped framebuffer.
+// Only `mfence` and `sfence` drain the write-combining buffers, so a locked instruction would weaken these barriers rather than strengthen them.
+// We operate on the top of the stack instead of below it, since the area below the stack pointer is the red zone on x86-64.
+
+#[cfg(all(feature = "stdarch", mem_barrier_x86_fence))]
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, ty: BarrierType) {
     #[cfg(target_arch = "x86")]
     use core::arch::x86::{_mm_lfence, _mm_mfence, _mm_sfence};
     #[cfg(target_arch = "x86_64")]
@@ -11,35 +27,243 @@ pub fn mem_barrier(_kind: CpuBarrierKind, ty: BarrierType) {
 
     // SAFETY: This is just a memory fence.
     unsafe {
-        match ty {
-            BarrierType::General => {
+        match (kind, ty) {
+            (
+                CpuBarrierKind::Smp,
+                BarrierType::Read
+                | BarrierType::Write
+                | BarrierType::Acquire
+                | BarrierType::Release,
+            ) => {
+                crate::compiler_barrier();
+            }
+            #[cfg(feature = "x86-lock-fence")]
+            (CpuBarrierKind::Smp, BarrierType::General) => lock_fence(),
+            (_, BarrierType::General | BarrierType::Acquire | BarrierType::Release) => {
                 _mm_mfence();
             }
-            BarrierType::Read => {
+            (_, BarrierType::Read) => {
                 _mm_lfence();
             }
-            BarrierType::Write => {
+            (_, BarrierType::Write) => {
                 _mm_sfence();
             }
         }
     }
 }
 
-#[cfg(not(feature = "stdarch"))]
-#[inline]
-pub fn mem_barrier(_kind: CpuBarrierKind, ty: BarrierType) {
+// The inline assembly implementation is always compiled, so that `crate::mem_barrier_asm` can compare it against the intrinsics.
+// Without SSE2 at build time, there is no intrinsic implementation.
+
+#[cfg(not(all(feature = "stdarch", mem_barrier_x86_fence)))]
+pub use self::mem_barrier_asm as mem_barrier;
+
+#[cfg(mem_barrier_x86_fence)]
+#[inline(always)]
+pub fn mem_barrier_asm(kind: CpuBarrierKind, ty: BarrierType) {
     // SAFETY: This is just a memory fence.
     unsafe {
-        match ty {
-            BarrierType::General => {
+        match (kind, ty) {
+            (
+                CpuBarrierKind::Smp,
+                BarrierType::Read
+                | BarrierType::Write
+                | BarrierType::Acquire
+                | BarrierType::Release,
+            ) => {
+                crate::compiler_barrier();
+            }
+            #[cfg(feature = "x86-lock-fence")]
+            (CpuBarrierKind::Smp, BarrierType::General) => lock_fence(),
+            (_, BarrierType::General | BarrierType::Acquire | BarrierType::Release) => {
                 core::arch::asm!("mfence", options(preserves_flags, nostack));
             }
-            BarrierType::Read => {
+            (_, BarrierType::Read) => {
                 core::arch::asm!("lfence", options(preserves_flags, nostack));
             }
-            BarrierType::Write => {
+            (_, BarrierType::Write) => {
                 core::arch::asm!("sfence", options(preserves_flags, nostack));
             }
         }
     }
 }
+
+#[cfg(not(mem_barrier_x86_fence))]
+#[inline(always)]
+pub fn mem_barrier_asm(kind: CpuBarrierKind, ty: BarrierType) {
+    match (kind, ty) {
+        (
+            CpuBarrierKind::Smp,
+            BarrierType::Read | BarrierType::Write | BarrierType::Acquire | BarrierType::Release,
+        ) => {
+            crate::compiler_barrier();
+        }
+        _ => lock_fence(),
+    }
+}
+
+#[cfg(mem_barrier_x86_fence)]
+#[inline(always)]
+pub fn mem_barrier_with(_features: Features, kind: CpuBarrierKind, ty: BarrierType) {
+    mem_barrier(kind, ty);
+}
+
+#[cfg(not(mem_barrier_x86_fence))]
+#[inline(always)]
+pub fn mem_barrier_with(features: Features, kind: CpuBarrierKind, ty: BarrierType) {
+    match (kind, ty) {
+        (
+            CpuBarrierKind::Smp,
+            BarrierType::Read | BarrierType::Write | BarrierType::Acquire | BarrierType::Release,
+        ) => {
+            crate::compiler_barrier();
+        }
+        (CpuBarrierKind::Smp, BarrierType::General) => lock_fence(),
+        // SAFETY: These are just memory fences, which raise an exception if the processor does not implement SSE2.
+        (_, _) if features.contains(Features::SSE2) => unsafe {
+            match ty {
+                BarrierType::General | BarrierType::Acquire | BarrierType::Release => {
+                    core::arch::asm!("mfence", options(preserves_flags, nostack));
+                }
+                BarrierType::Read => {
+                    core::arch::asm!("lfence", options(preserves_flags, nostack));
+                }
+                BarrierType::Write => {
+                    core::arch::asm!("sfence", options(preserves_flags, nostack));
+                }
+            }
+        },
+        _ => lock_fence(),
+    }
+}
+
+#[cfg(any(feature = "x86-lock-fence", not(mem_barrier_x86_fence)))]
+#[inline(always)]
+fn lock_fence() {
+    // SAFETY: This atomically adds zero to the top of the stack, which does not change it.
+    unsafe {
+        #[cfg(target_arch = "x86")]
+        core::arch::asm!("lock add dword ptr [esp], 0", options(nostack));
+        #[cfg(target_arch = "x86_64")]
+        core::arch::asm!("lock add dword ptr [rsp], 0", options(nostack));
+    }
+}
+
+// `cpuid` is serializing on all x86 processors.
+// The dedicated `serialize` instruction is cheaper, especially in virtual machines, where `cpuid` traps to the hypervisor.
+// Rust does not expose a target feature for it, so only `instruction_barrier_with` uses it if the caller detected it at runtime.
+
+#[inline(always)]
+pub fn instruction_barrier() {
+    cpuid();
+}
+
+#[inline(always)]
+pub fn instruction_barrier_asm() {
+    cpuid_asm();
+}
+
+#[inline(always)]
+pub fn instruction_barrier_with(features: Features) {
+    if features.contains(Features::SERIALIZE) {
+        serialize();
+    } else {
+        cpuid();
+    }
+}
+
+#[inline(always)]
+fn serialize() {
+    // SAFETY: This is just a serializing instruction.
+    unsafe {
+        core::arch::asm!("serialize", options(preserves_flags, nostack));
+    }
+}
+
+#[cfg(not(feature = "stdarch"))]
+use self::cpuid_asm as cpuid;
+
+#[cfg(feature = "stdarch")]
+#[inline(always)]
+fn cpuid() {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::__cpuid;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::__cpuid;
+
+    __cpuid(0);
+}
+
+#[inline(always)]
+fn cpuid_asm() {
+    // `cpuid` writes to `eax`, `ebx`, `ecx`, and `edx`.
+    // LLVM reserves `rbx`, so we have to save and restore it manually.
+    //
+    // SAFETY: `cpuid` with leaf 0 only writes to the declared registers.
+    unsafe {
+        #[cfg(target_arch = "x86")]
+        core::arch::asm!(
+            "mov {tmp:e}, ebx",
+            "cpuid",
+            "mov ebx, {tmp:e}",
+            tmp = out(reg) _,
+            inout("eax") 0 => _,
+            out("ecx") _,
+            out("edx") _,
+            options(preserves_flags, nostack)
+        );
+        #[cfg(target_arch = "x86_64")]
+        core::arch::asm!(
+            "mov {tmp:r}, rbx",
+            "cpuid",
+            "mov rbx, {tmp:r}",
+            tmp = out(reg) _,
+            inout("eax") 0 => _,
+            out("ecx") _,
+            out("edx") _,
+            options(preserves_flags, nostack)
+        );
+    }
+}
+
+#[cfg(mem_barrier_x86_fence)]
+pub const fn mnemonic(kind: CpuBarrierKind, ty: BarrierType) -> &'static str {
+    match (kind, ty) {
+        (
+            CpuBarrierKind::Smp,
+            BarrierType::Read | BarrierType::Write | BarrierType::Acquire | BarrierType::Release,
+        ) => "",
+        #[cfg(feature = "x86-lock-fence")]
+        (CpuBarrierKind::Smp, BarrierType::General) => LOCK_FENCE,
+        (_, BarrierType::General | BarrierType::Acquire | BarrierType::Release) => "mfence",
+        (_, BarrierType::Read) => "lfence",
+        (_, BarrierType::Write) => "sfence",
+    }
+}
+
+#[cfg(not(mem_barrier_x86_fence))]
+pub const fn mnemonic(kind: CpuBarrierKind, ty: BarrierType) -> &'static str {
+    match (kind, ty) {
+        (
+            CpuBarrierKind::Smp,
+            BarrierType::Read | BarrierType::Write | BarrierType::Acquire | BarrierType::Release,
+        ) => "",
+        _ => LOCK_FENCE,
+    }
+}
+
+#[cfg(all(
+    target_arch = "x86",
+    any(feature = "x86-lock-fence", not(mem_barrier_x86_fence))
+))]
+const LOCK_FENCE: &str = "lock add dword ptr [esp], 0";
+#[cfg(all(target_arch = "x86_64", feature = "x86-lock-fence"))]
+const LOCK_FENCE: &str = "lock add dword ptr [rsp], 0";
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    "cpuid"
+}
+
+pub const USES_STDARCH: bool = cfg!(feature = "stdarch");
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
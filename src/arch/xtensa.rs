@@ -0,0 +1,62 @@
+use super::CpuBarrierKind;
+use crate::BarrierType;
+
+// `memw` orders all earlier memory accesses before all later memory accesses, including device accesses.
+// `extw` additionally waits for the external effects of earlier instructions, such as device writes, to complete.
+// Xtensa does not distinguish between reads and writes, so read and write barriers are full barriers.
+//
+// Inline assembly is unstable on Xtensa.
+// Without the `nightly` feature, we use a sequentially consistent atomic fence, which compiles to `memw`.
+
+#[cfg(not(mem_barrier_asm))]
+#[inline(always)]
+pub fn mem_barrier(_kind: CpuBarrierKind, _ty: BarrierType) {
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(mem_barrier_asm)]
+#[inline(always)]
+pub fn mem_barrier(kind: CpuBarrierKind, _ty: BarrierType) {
+    // SAFETY: This is just a memory barrier.
+    unsafe {
+        match kind {
+            CpuBarrierKind::Mmio => core::arch::asm!("extw", options(preserves_flags, nostack)),
+            CpuBarrierKind::Smp | CpuBarrierKind::Dma => {
+                core::arch::asm!("memw", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
+
+#[inline(always)]
+pub fn instruction_barrier() {
+    cfg_if::cfg_if! {
+        if #[cfg(mem_barrier_asm)] {
+            // SAFETY: This is just an instruction fetch synchronization.
+            unsafe {
+                core::arch::asm!("isync", options(preserves_flags, nostack));
+            }
+        } else {
+            super::fallback::instruction_barrier();
+        }
+    }
+}
+
+pub const fn mnemonic(kind: CpuBarrierKind, _ty: BarrierType) -> &'static str {
+    match kind {
+        CpuBarrierKind::Mmio if cfg!(mem_barrier_asm) => "extw",
+        CpuBarrierKind::Mmio | CpuBarrierKind::Smp | CpuBarrierKind::Dma => "memw",
+    }
+}
+
+pub const fn instruction_barrier_mnemonic() -> &'static str {
+    if cfg!(mem_barrier_asm) {
+        "isync"
+    } else {
+        "memw"
+    }
+}
+
+pub const USES_STDARCH: bool = false;
+
+pub const USES_NIGHTLY_INTRINSICS: bool = false;
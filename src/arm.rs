@@ -0,0 +1,161 @@
+//! ARM-specific barriers.
+
+/// The shareability domain of a [`dsb`].
+///
+/// The domain selects the observers for which the barrier waits for memory accesses to complete.
+/// Only ARMv7 and later A-profile and R-profile cores distinguish between domains.
+/// Other cores always use the full system.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Domain {
+    /// The non-shareable domain (`NSH`), which only contains the current CPU.
+    NonShareable,
+
+    /// The inner shareable domain (`ISH`), which usually contains all CPUs running the same OS.
+    InnerShareable,
+
+    /// The outer shareable domain (`OSH`), which also contains coherent devices outside the inner shareable domain.
+    OuterShareable,
+
+    /// The full system (`SY`), which contains all agents in the system.
+    #[default]
+    FullSystem,
+}
+
+/// A data synchronization barrier.
+///
+/// On ARMv7 and later, this runs a [DSB] instruction with the given domain.
+/// Write barriers use the store-only option (`ST`), while all other barrier types use the full barrier, since ARMv7 has no load-only options.
+///
+/// Unlike the `DMB` instructions run by most kinds of [`mem_barrier`](fn@crate::mem_barrier), this is a completion barrier:
+/// It does not return until memory accesses before the barrier have completed for all observers in the domain.
+/// This is required before a side effect that memory ordering does not cover, such as gating a clock, entering a low-power state with `WFI`, or changing the power state of a device.
+///
+/// # Current implementation
+///
+/// On M-profile cores, this runs a `DSB` instruction without an option, which is equivalent to `SY`.
+/// On ARMv6, this runs the CP15 DSB operation.
+/// On ARMv5 and earlier, this drains the write buffer via CP15.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(target_arch = "arm")]
+/// # {
+/// use mem_barrier::BarrierType;
+/// use mem_barrier::arm::{Domain, dsb};
+///
+/// // Write to the power controller here.
+///
+/// // Wait for the write to complete before entering the low-power state.
+/// dsb(Domain::FullSystem, BarrierType::Write);
+/// // Run WFI here.
+/// # }
+/// ```
+///
+/// [DSB]: https://developer.arm.com/documentation/ddi0597/2025-09/Base-Instructions/DSB--Data-Synchronization-Barrier-
+#[doc(alias = "data_sync_barrier")]
+#[inline(always)]
+pub fn dsb(domain: Domain, ty: crate::BarrierType) {
+    cfg_if::cfg_if! {
+        if #[cfg(any(miri, feature = "loom"))] {
+            // Miri cannot run inline assembly, and loom cannot reason about it.
+            let _ = (domain, ty);
+            crate::arch::fallback::fence(core::sync::atomic::Ordering::SeqCst);
+        } else {
+            crate::arch::dsb(domain, ty);
+        }
+    }
+}
+
+/// Sends an event to all CPUs.
+///
+/// On ARMv7 and M-profile cores, this runs a [SEV] instruction, which wakes up CPUs waiting in [`wait_for_event`].
+/// On other cores, this is a no-op.
+///
+/// This is not a memory barrier, and `DMB` instructions do not order it either.
+/// To make memory writes visible to the woken CPUs, issue a [`dsb`] before sending the event.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(target_arch = "arm")]
+/// # {
+/// use core::sync::atomic::{AtomicBool, Ordering};
+///
+/// use mem_barrier::BarrierType;
+/// use mem_barrier::arm::{Domain, dsb, send_event, wait_for_event};
+///
+/// static READY: AtomicBool = AtomicBool::new(false);
+///
+/// // Publish the data, then wake up the waiting CPUs.
+/// READY.store(true, Ordering::Release);
+/// dsb(Domain::InnerShareable, BarrierType::Write);
+/// send_event();
+///
+/// // On the waiting CPU, sleep until the data is published.
+/// while !READY.load(Ordering::Acquire) {
+///     wait_for_event();
+/// }
+/// # }
+/// ```
+///
+/// [SEV]: https://developer.arm.com/documentation/ddi0597/2025-09/Base-Instructions/SEV--Send-Event-
+#[doc(alias = "sev")]
+#[inline(always)]
+pub fn send_event() {
+    cfg_if::cfg_if! {
+        if #[cfg(any(
+            miri,
+            feature = "loom",
+            not(any(
+                mem_barrier_arm = "v6m",
+                mem_barrier_arm = "v7m",
+                mem_barrier_arm = "v7"
+            ))
+        ))] {
+            // Miri cannot run inline assembly, and older cores have no events.
+            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+        } else {
+            // SAFETY: This is just a hint.
+            unsafe {
+                core::arch::asm!("sev", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
+
+/// Waits for an event.
+///
+/// On ARMv7 and M-profile cores, this runs a [WFE] instruction, which may put the CPU into a low-power state until an event, such as one sent by [`send_event`], or an interrupt wakes it up.
+/// On other cores, this is a spin loop hint that returns immediately.
+///
+/// This is not a memory barrier.
+/// The CPU may also wake up spuriously, so callers have to check their wakeup condition in a loop.
+/// See [`send_event`] for an example.
+///
+/// [WFE]: https://developer.arm.com/documentation/ddi0597/2025-09/Base-Instructions/WFE--Wait-For-Event-
+#[doc(alias = "wfe")]
+#[inline(always)]
+pub fn wait_for_event() {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "loom")] {
+            // This lets loom schedule other threads while waiting.
+            loom::hint::spin_loop();
+        } else if #[cfg(any(
+            miri,
+            not(any(
+                mem_barrier_arm = "v6m",
+                mem_barrier_arm = "v7m",
+                mem_barrier_arm = "v7"
+            ))
+        ))] {
+            // Miri cannot run inline assembly, and older cores have no events.
+            crate::spin_loop_hint();
+        } else {
+            // SAFETY: This is just a hint.
+            unsafe {
+                core::arch::asm!("wfe", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
@@ -4,7 +4,8 @@
 //! Modern CPUs use similar techniques for improving performance, such as out-of-order execution.
 //! Memory barriers affect both the compiler and the CPU by restricting reordering of certain memory operations across these barriers respective to other CPUs or devices, allowing proper communication with them.
 //!
-//! To insert a memory barrier, use the [`mem_barrier`] function.
+//! To insert a memory barrier, use the [`mem_barrier`](fn@mem_barrier) function or the [`mem_barrier!`] macro.
+//! To insert the weakest memory barrier that enforces an atomic memory ordering within a scope, use [`mem_barrier_for`] or [`Barrier::for_ordering`].
 //!
 //! The memory barriers provided by this crate are similar to the [Linux kernel memory barriers].
 //! For more details on _that_ API, also see the [_Linux Kernel Memory Consistency Model_ (LKMM)].
@@ -25,17 +26,129 @@
 //! | Architecture | `target_arch` | Supported |
 //! | ------------ | ------------- | --------- |
 //! | AArch64      | `aarch64`     | ✅        |
+//! | ARM          | `arm`         | ✅        |
+//! | AVR          | `avr`         | ✅        |
+//! | BPF          | `bpf`         | ✅        |
+//! | C-SKY        | `csky`        | ✅        |
+//! | Hexagon      | `hexagon`     | ✅        |
+//! | LoongArch64  | `loongarch64` | ✅        |
+//! | m68k         | `m68k`        | ✅        |
+//! | MIPS         | `mips`        | ✅        |
+//! | MIPS32 R6    | `mips32r6`    | ✅        |
+//! | MIPS64       | `mips64`      | ✅        |
+//! | MIPS64 R6    | `mips64r6`    | ✅        |
+//! | NVPTX64      | `nvptx64`     | ✅        |
+//! | PowerPC      | `powerpc`     | ✅        |
+//! | PowerPC64    | `powerpc64`   | ✅        |
 //! | RISC-V RV32  | `riscv32`     | ✅        |
 //! | RISC-V RV64  | `riscv64`     | ✅        |
+//! | s390x        | `s390x`       | ✅        |
+//! | SPARC        | `sparc`       | ✅        |
+//! | SPARC64      | `sparc64`     | ✅        |
+//! | WebAssembly  | `wasm32`      | ✅        |
+//! | WebAssembly  | `wasm64`      | ✅        |
 //! | x86          | `x86`         | ✅        |
 //! | x86-64       | `x86_64`      | ✅        |
+//! | Xtensa       | `xtensa`      | ✅        |
+//!
+//! AArch64 support includes ILP32 targets such as `arm64_32-apple-watchos`, which use the same barrier instructions.
+//!
+//! Support for NVPTX is best-effort and intended for sharing code between the host and the GPU.
+//! SMP barriers order memory accesses as observed by other GPU threads (`MEMBAR.GL`), while MMIO and DMA barriers order them as observed by the host as well (`MEMBAR.SYS`).
+//! Without the `nightly` feature, all barriers on NVPTX, including compiler barriers, run a sequentially consistent fence.
+//!
+//! BPF has no barrier instructions, so all barriers are compiler barriers only on BPF.
+//! Since LLVM cannot compile compiler fences for BPF either, BPF requires the `nightly` feature.
+//!
+//! On other architectures, this crate falls back to [`core::sync::atomic::fence`].
+//! This is a conservative, portable fallback that may be slower than necessary.
+//! It requires the `fallback` feature.
+//!
+//! Under [Miri], which cannot run inline assembly, all barriers use this fallback as well.
+//!
+//! [Miri]: https://github.com/rust-lang/miri
+//!
+//! On ARM, the available barriers depend on the architecture version:
+//!
+//! | Architecture version | [`Mmio`]                | [`Smp`]   | [`Dma`]                 |
+//! | -------------------- | ----------------------- | --------- | ----------------------- |
+//! | ARMv7 and later      | `DSB`                   | `DMB ISH` | `DMB OSH`               |
+//! | M-profile            | `DSB`                   | `DMB`     | `DMB`                   |
+//! | ARMv6                | CP15 DSB                | CP15 DMB  | CP15 DMB                |
+//! | ARMv5 and earlier    | CP15 drain write buffer | none      | CP15 drain write buffer |
+//!
+//! ARMv5 and earlier cores are uniprocessor, so SMP barriers are compiler barriers only.
+//! Before ARMv7 and on M-profile cores, read and write barriers are full barriers.
+//! Thumb-1 cannot encode CP15 operations, so on Thumb-1 targets they are out-of-line A32 functions.
+//!
+//! ## Shareability domains
+//!
+//! On AArch64, each kind of data barrier orders memory accesses as observed within a shareability domain:
+//!
+//! | Barrier kind               | Domain | Observers                                                                            |
+//! | -------------------------- | ------ | ------------------------------------------------------------------------------------ |
+//! | [`NonShareable`]           | `NSH`  | The current CPU                                                                      |
+//! | [`Smp`], [`Virt`]          | `ISH`  | CPUs in the inner shareable domain, usually all CPUs running the same OS             |
+//! | [`Dma`], [`DmaCompletion`] | `OSH`  | Coherent agents in the outer shareable domain, such as CCIX- or CXL-attached devices |
+//! | [`System`], [`Mmio`]       | `SY`   | All agents in the system                                                             |
+//!
+//! If memory is shared with a coherent accelerator or mapped as outer shareable, [`Smp`] barriers are too weak.
+//! Use [`Dma`] barriers instead, which run `DMB OSH`.
+//! If the sharing domain is not known or the agent is outside the outer shareable domain, use [`System`] barriers, which run `DMB SY`.
+//!
+//! [`Mmio`]: BarrierKind::Mmio
+//! [`Smp`]: BarrierKind::Smp
+//! [`Virt`]: BarrierKind::Virt
+//! [`Dma`]: BarrierKind::Dma
+//! [`DmaCompletion`]: BarrierKind::DmaCompletion
+//! [`System`]: BarrierKind::System
+//! [`NonShareable`]: BarrierKind::NonShareable
 //!
 //! # Cargo features
 //!
 //! This crate has the following Cargo features:
 //! - `nightly`—Disabled by default, this feature enables memory barrier implementations based on unstable, nightly-only Rust features.
+//!   It also enables `mem_barrier_const`, which requires unstable const generics.
+//! - `fallback`—Enabled by default, this feature enables the fallback on architectures that are not supported otherwise.
+//!   Without this feature, compiling for such an architecture fails with an error.
+//!   On supported architectures, this feature has no effect.
 //! - `stdarch`—Enabled by default, this feature enables memory barrier implementations based on [`core::arch`] intrinsics.
 //!   If available, these intrinsics replace the fallback implementations based on inline assembly.
+//!   On AArch64 and ARM, the barrier intrinsics are unstable, so they are only used if the `nightly` feature is enabled as well.
+//!   Otherwise, the inline assembly implementations run the same instructions, so this feature does not change behavior on stable.
+//!   [`mem_barrier_asm`] always runs the inline assembly implementations, regardless of this feature.
+//! - `x86-lock-fence`—Disabled by default, this feature makes general SMP barriers on x86 run a locked [ADD] instruction on the stack instead of an [MFENCE] instruction.
+//!   This is faster on many processors.
+//!   MMIO and DMA barriers still run MFENCE, since locked instructions do not order non-temporal stores.
+//! - `single_core`—Disabled by default, this feature makes [`Smp`] barriers compiler barriers only.
+//!   Only enable this feature for uniprocessor systems, where no other CPU can observe reordering.
+//!   MMIO and DMA barriers are not affected, since devices still observe reordering.
+//!   [`Virt`] barriers are not affected either.
+//! - `serde`—Disabled by default, this feature implements [`Serialize`] and [`Deserialize`] for [`BarrierKind`] and [`BarrierType`].
+//!   Both serialize as the names of their [`Display`](core::fmt::Display) implementations, such as `smp`.
+//! - `defmt`—Disabled by default, this feature implements [`defmt::Format`] for [`BarrierKind`] and [`BarrierType`].
+//! - `trace`—Disabled by default, this feature adds `set_trace_hook`, which registers a function that is called before every memory barrier.
+//!   Without this feature, there is no overhead.
+//!   The hook is stored in an atomic pointer, so on targets without pointer atomics, such as ARMv4T and ARMv5TE, this feature requires the `portable-atomic` feature as well.
+//!   On such targets, portable-atomic in turn requires its `critical-section` feature or the `portable_atomic_unsafe_assume_single_core` cfg.
+//! - `loom`—Disabled by default, this feature makes all CPU barriers run [`loom::sync::atomic::fence`] instead of CPU instructions, so that [loom] can model-check code that uses them.
+//!   [`spin_loop_hint`] runs [`loom::hint::spin_loop`].
+//!   With this feature, barriers may only run inside [`loom::model`].
+//!   This feature requires `std` and is intended for tests only.
+//! - `portable-atomic`—Disabled by default, this feature makes the fallback barriers described above run [`portable_atomic::fence`] instead of [`core::sync::atomic::fence`].
+//!   If the `loom` feature is enabled as well, loom's fence takes precedence.
+//! - `ffi`—Disabled by default, this feature exports `mem_barrier_ffi`, a C ABI entry point for [`mem_barrier`](fn@mem_barrier).
+//!
+//! [`defmt::Format`]: https://docs.rs/defmt/1/defmt/trait.Format.html
+//! [loom]: https://docs.rs/loom/0.7
+//! [`loom::sync::atomic::fence`]: https://docs.rs/loom/0.7/loom/sync/atomic/fn.fence.html
+//! [`loom::hint::spin_loop`]: https://docs.rs/loom/0.7/loom/hint/fn.spin_loop.html
+//! [`loom::model`]: https://docs.rs/loom/0.7/loom/fn.model.html
+//! [`portable_atomic::fence`]: https://docs.rs/portable-atomic/1/portable_atomic/fn.fence.html
+//! [`Serialize`]: https://docs.rs/serde/1/serde/trait.Serialize.html
+//! [`Deserialize`]: https://docs.rs/serde/1/serde/trait.Deserialize.html
+//! [ADD]: https://www.felixcloutier.com/x86/add
+//! [MFENCE]: https://www.felixcloutier.com/x86/mfence
 //!
 //! # Related crates
 //!
@@ -48,21 +161,93 @@
 
 #![no_std]
 #![cfg_attr(
-    all(target_arch = "aarch64", feature = "stdarch", feature = "nightly"),
+    all(
+        any(
+            target_arch = "aarch64",
+            all(
+                target_arch = "arm",
+                not(mem_barrier_arm = "v5"),
+                not(mem_barrier_arm_thumb1)
+            )
+        ),
+        feature = "stdarch",
+        feature = "nightly"
+    ),
     feature(stdarch_arm_barrier)
 )]
+#![cfg_attr(
+    all(
+        target_arch = "arm",
+        not(mem_barrier_arm = "v5"),
+        not(mem_barrier_arm_thumb1),
+        feature = "stdarch",
+        feature = "nightly"
+    ),
+    feature(stdarch_arm_neon_intrinsics)
+)]
+#![cfg_attr(mem_barrier_asm_experimental_arch, feature(asm_experimental_arch))]
+#![cfg_attr(feature = "nightly", feature(adt_const_params))]
+
+use core::marker::PhantomData;
+use core::ops::{BitOr, BitOrAssign};
+use core::str::FromStr;
+#[cfg(all(
+    feature = "trace",
+    not(feature = "portable-atomic"),
+    target_has_atomic = "ptr"
+))]
+use core::sync::atomic::AtomicPtr;
+use core::sync::atomic::Ordering;
+use core::{cmp, fmt};
+#[cfg(feature = "trace")]
+use core::{mem, ptr};
+
+#[cfg(all(feature = "trace", feature = "portable-atomic"))]
+use portable_atomic::AtomicPtr;
+
+#[cfg(all(
+    feature = "trace",
+    not(feature = "portable-atomic"),
+    not(target_has_atomic = "ptr")
+))]
+compile_error!(
+    "the `trace` feature requires pointer atomics. Enable the `portable-atomic` feature to use it on this target."
+);
 
+#[cfg(target_arch = "aarch64")]
+pub mod aarch64;
 mod arch;
+#[cfg(target_arch = "arm")]
+pub mod arm;
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+pub mod riscv;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub mod x86;
 
 /// The kind of a memory barrier.
 ///
 /// This enum determines the strength or flavor of the memory barrier.
 ///
+/// # Representation
+///
+/// This enum is represented as a `u8`.
+/// The discriminants of existing variants are stable and new variants receive new discriminants, which allows encoding barrier kinds as a single byte.
+/// Use [`TryFrom<u8>`] to decode them.
+///
 /// # Current implementation
 ///
-/// On x86, this does not affect instruction generation.
+/// On x86, SMP read, write, acquire, and release barriers are compiler barriers only, since x86 is strongly ordered.
+/// Otherwise, this does not affect instruction generation.
 #[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "nightly", derive(core::marker::ConstParamTy))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 #[non_exhaustive]
+#[repr(u8)]
 pub enum BarrierKind {
     /// MMIO.
     ///
@@ -77,44 +262,77 @@ pub enum BarrierKind {
     ///
     /// On Arm, this runs a [DSB] instruction; see _[Data Synchronization Barrier]_.
     ///
+    /// On x86 with SSE2, read barriers run an [LFENCE] instruction, write barriers run an [SFENCE] instruction, and all other barrier types run an [MFENCE] instruction.
+    /// These fences also order non-temporal stores and stores to write-combining memory, such as a mapped framebuffer, which locked instructions are not guaranteed to order.
+    /// For this reason, this kind of barrier never runs a locked instruction, even with the `x86-lock-fence` feature.
+    /// Write barriers suffice for flushing write-combining stores before notifying a device.
+    /// If reads from the device must not pass earlier write-combining stores, use a general barrier.
+    ///
     /// [DSB]: https://developer.arm.com/documentation/ddi0602/2025-09/Base-Instructions/DSB--Data-synchronization-barrier-
     /// [Data Synchronization Barrier]: https://developer.arm.com/documentation/102336/0100/Data-Synchronization-Barrier
+    /// [LFENCE]: https://www.felixcloutier.com/x86/lfence
+    /// [SFENCE]: https://www.felixcloutier.com/x86/sfence
+    /// [MFENCE]: https://www.felixcloutier.com/x86/mfence
     #[doc(alias = "mb")]
     #[doc(alias = "rmb")]
     #[doc(alias = "wmb")]
     #[default]
-    Mmio,
+    Mmio = 0,
 
     /// SMP.
     ///
     /// This kind of barrier enforces ordering on memory across an SMP system.
-    /// It is also suitable to enforce ordering on memory shared from a single-CPU VM guest with an SMP host.
     ///
     /// # Corresponding functions
     ///
-    /// This kind of barrier corresponds to the _VM-guest_-flavoured `virt_mb`, `virt_rmb`, and `virt_wmb` Linux functions.
-    /// Those functions are equivalent to the _SMP_-flavoured `smp_mb`, `smp_rmb`, and `smp_wmb` Linux functions when SMP support is turned on.
+    /// This kind of barrier corresponds to the _SMP_-flavoured `smp_mb`, `smp_rmb`, and `smp_wmb` Linux functions.
     ///
-    /// Note that this kind of barrier does not change its behavior based on the build configuration.
+    /// Like those functions, this kind of barrier changes its behavior based on the build configuration.
+    /// With the `single_core` feature, this is a compiler barrier only, like the `smp_*` Linux functions when SMP support is turned off.
+    /// For memory shared with other agents regardless of the build configuration, such as a VM host, use [`Virt`](Self::Virt) instead.
+    /// For memory shared with coherent devices in the outer shareable domain, use [`Dma`](Self::Dma) instead; see [_Shareability domains_](crate#shareability-domains).
     ///
     /// # Current implementation
     ///
     /// On Arm, this runs a [DMB] instruction; see _[Data Memory Barrier]_.
     ///
+    /// On RISC-V with the Ztso extension (`target_feature = "ztso"`), read, write, acquire, and release barriers of this kind are compiler barriers only, like on x86.
+    ///
+    /// On LoongArch, this runs a [DBAR] instruction with an ordering hint, such as `dbar 0x15` for read barriers.
+    /// Other kinds of barriers use completion hints, such as `dbar 0x5`.
+    /// Cores that do not implement a hint run the full `dbar 0` barrier instead.
+    ///
     /// [DMB]: https://developer.arm.com/documentation/ddi0602/2025-09/Base-Instructions/DMB--Data-memory-barrier-
     /// [Data Memory Barrier]: https://developer.arm.com/documentation/102336/0100/Data-Memory-Barrier
+    /// [DBAR]: https://loongson.github.io/LoongArch-Documentation/LoongArch-Vol1-EN.html#dbar
     #[doc(alias = "smp_mb")]
     #[doc(alias = "smp_rmb")]
     #[doc(alias = "smp_wmb")]
+    Smp = 1,
+
+    /// Virtualization.
+    ///
+    /// This kind of barrier enforces ordering on memory shared with other agents that always run concurrently, such as the host of a single-CPU VM guest.
+    ///
+    /// # Corresponding functions
+    ///
+    /// This kind of barrier corresponds to the _VM-guest_-flavoured `virt_mb`, `virt_rmb`, and `virt_wmb` Linux functions.
+    ///
+    /// Unlike [`Smp`](Self::Smp), this kind of barrier does not change its behavior based on the build configuration.
+    /// It is the same as [`Smp`](Self::Smp) without the `single_core` feature and still runs the SMP barrier instructions with that feature.
     #[doc(alias = "virt_mb")]
     #[doc(alias = "virt_rmb")]
     #[doc(alias = "virt_wmb")]
-    Smp,
+    Virt = 8,
 
     /// DMA.
     ///
     /// This kind of barrier enforces ordering on memory accessed by the CPU and DMA-capable devices.
     ///
+    /// This is an observability barrier: devices observe memory accesses before the barrier before those after the barrier.
+    /// It does not wait for memory accesses to complete, though.
+    /// If memory writes need to have reached the device before continuing, use [`DmaCompletion`](Self::DmaCompletion) instead.
+    ///
     /// # Corresponding functions
     ///
     /// This kind of barrier corresponds to the _DMA_-flavoured `dma_mb`, `dma_rmb`, and `dma_wmb` Linux functions.
@@ -168,18 +386,18 @@ pub enum BarrierKind {
     /// # let mut read_data = data;
     /// # let mut write_data = data;
     /// #
-    /// use mem_barrier::{BarrierKind, BarrierType, mem_barrier};
+    /// use mem_barrier::{dma_rmb, dma_wmb};
     ///
     /// if !desc.device_owns_memory() {
     ///     // Don't read until we own the descriptor.
-    ///     mem_barrier(BarrierKind::Dma, BarrierType::Read);
+    ///     dma_rmb();
     ///
     ///     // Read/modify data
     ///     read_data = desc.data();
     ///     desc.set_data(write_data);
     ///
     ///     // Flush modifications.
-    ///     mem_barrier(BarrierKind::Dma, BarrierType::Write);
+    ///     dma_wmb();
     ///
     ///     // Give the descriptor ownership back to the device.
     ///     desc.set_device_owns_memory(true);
@@ -191,24 +409,342 @@ pub enum BarrierKind {
     #[doc(alias = "dma_mb")]
     #[doc(alias = "dma_rmb")]
     #[doc(alias = "dma_wmb")]
-    Dma,
+    Dma = 2,
+
+    /// DMA completion.
+    ///
+    /// This kind of barrier is like [`Dma`](Self::Dma) but also waits for memory accesses before the barrier to complete before continuing.
+    /// This is a completion barrier: once it returns, memory writes have reached the point where DMA-capable devices observe them.
+    ///
+    /// Use this barrier if a device has to observe memory writes before a side effect that [`Dma`](Self::Dma) barriers do not order, such as ringing a doorbell.
+    /// Otherwise, prefer [`Dma`](Self::Dma), which is cheaper.
+    ///
+    /// # Current implementation
+    ///
+    /// On AArch64, this runs a [DSB] instruction with the outer shareable domain (`OSH`, `OSHLD`, or `OSHST`).
+    /// On other architectures, this is the same as [`Mmio`](Self::Mmio).
+    ///
+    /// [DSB]: https://developer.arm.com/documentation/ddi0602/2025-09/Base-Instructions/DSB--Data-synchronization-barrier-
+    DmaCompletion = 3,
+
+    /// Full system.
+    ///
+    /// This kind of barrier is like [`Dma`](Self::Dma) but enforces ordering as observed by all agents in the system, regardless of their shareability domain.
+    /// Use this barrier for memory shared with agents outside the outer shareable domain or if the sharing domain is unknown.
+    ///
+    /// See [_Shareability domains_](crate#shareability-domains) for choosing between this and the other kinds.
+    ///
+    /// # Current implementation
+    ///
+    /// On AArch64, this runs a [DMB] instruction with the full system domain (`SY`, `LD`, or `ST`).
+    /// On other architectures, this is the same as [`Dma`](Self::Dma).
+    ///
+    /// [DMB]: https://developer.arm.com/documentation/ddi0602/2025-09/Base-Instructions/DMB--Data-memory-barrier-
+    System = 9,
+
+    /// Non-shareable.
+    ///
+    /// This kind of barrier only enforces ordering on memory accesses as observed by the current CPU.
+    /// It does not enforce ordering as observed by other CPUs or devices.
+    /// This is useful for flows such as cache maintenance that only concern the current CPU.
+    ///
+    /// # Current implementation
+    ///
+    /// On AArch64, this runs a [DMB] instruction with a non-shareable domain (`NSH`, `NSHLD`, or `NSHST`).
+    /// On other architectures, this is the same as [`Smp`](Self::Smp).
+    ///
+    /// [DMB]: https://developer.arm.com/documentation/ddi0602/2025-09/Base-Instructions/DMB--Data-memory-barrier-
+    NonShareable = 4,
+
+    /// Device.
+    ///
+    /// This kind of barrier only enforces ordering between device I/O accesses, such as two accesses to device registers.
+    /// It does not order device I/O against normal memory accesses.
+    /// If normal memory accesses are involved, such as a DMA buffer written before ringing a doorbell, use [`Mmio`](Self::Mmio) instead.
+    ///
+    /// Many architectures already keep device I/O accesses in program order, which makes this kind of barrier much cheaper than [`Mmio`](Self::Mmio).
+    ///
+    /// # Current implementation
+    ///
+    /// On Arm, only accesses to non-Reordering (nR) Device memory within the same peripheral are kept in program order.
+    /// Device-GRE memory and accesses to different peripherals may still be reordered, so this runs the same [DMB] instruction with an outer shareable domain as [`Dma`](Self::Dma) barriers (`OSH`, `OSHLD`, or `OSHST`), which is cheaper than the DSB of [`Mmio`](Self::Mmio) barriers.
+    /// On RISC-V, this runs a [FENCE] instruction with only device input and output in its sets.
+    /// On x86, accesses to uncacheable memory are strongly ordered, so this is a compiler barrier only.
+    /// This does not hold for write-combining memory, which needs an [`Mmio`](Self::Mmio) barrier.
+    /// On other architectures, this is the same as [`Mmio`](Self::Mmio).
+    ///
+    /// [DMB]: https://developer.arm.com/documentation/ddi0602/2025-09/Base-Instructions/DMB--Data-memory-barrier-
+    /// [FENCE]: https://docs.riscv.org/reference/isa/unpriv/rv32.html#fence
+    Device = 5,
 
     /// Compiler.
     ///
     /// This kind of barrier does not run any CPU instructions.
     /// Instead, it only prevents the compiler from moving memory accesses through the barrier.
+    /// On some architectures, this requires the `nightly` feature, as described for [`compiler_barrier`].
+    ///
+    /// The barrier type selects the ordering of the [`compiler_fence`](core::sync::atomic::compiler_fence):
+    /// General barriers are [`SeqCst`](Ordering::SeqCst), read and acquire barriers are [`Acquire`](Ordering::Acquire), and write and release barriers are [`Release`](Ordering::Release).
     ///
     /// # Corresponding functions
     ///
     /// This kind of barrier corresponds to the `barrier` Linux function.
-    Compiler,
+    /// General compiler barriers are also available as [`compiler_barrier`].
+    Compiler = 6,
+
+    /// Instruction.
+    ///
+    /// This kind of barrier synchronizes the instruction stream.
+    /// Instructions after the barrier are only fetched once the barrier completes, so they observe the effects of earlier context-changing operations, such as writing system registers, changing page tables, or writing code.
+    ///
+    /// This is not a data memory barrier and does not replace one.
+    /// To make earlier memory accesses visible before synchronizing the instruction stream, precede this with an [`Mmio`](Self::Mmio) barrier.
+    /// This does not perform any cache maintenance either, which might still be required when writing code.
+    ///
+    /// The [`BarrierType`] does not affect this kind of barrier.
+    ///
+    /// # Corresponding functions
+    ///
+    /// This kind of barrier corresponds to the `isb` and `sync_core` Linux functions.
+    ///
+    /// # Current implementation
+    ///
+    /// On Arm, this runs an [ISB] instruction.
+    /// On RISC-V, this runs a [FENCE.I] instruction, which only synchronizes the local hart.
+    /// On x86, this always runs a [CPUID] instruction, which is serializing.
+    /// Rust does not expose a target feature for the [SERIALIZE] instruction, so it only runs via `x86::mem_barrier_with` if it was detected at runtime.
+    /// This barrier does not consult `x86::features` itself, because that would add a runtime check to every instruction barrier and make [`barrier_mnemonic`] depend on the processor.
+    /// To use SERIALIZE where it is available, pass the cached features to `x86::mem_barrier_with` instead.
+    /// On PowerPC, this runs an `isync` instruction, which discards prefetched instructions.
+    /// After modifying code, PowerPC requires the sequence `dcbst; sync; icbi; sync; isync`.
+    /// Its `sync` steps are general [`Mmio`](Self::Mmio) barriers, and its `isync` step is this kind of barrier, while the cache operations are up to the caller.
+    ///
+    /// [ISB]: https://developer.arm.com/documentation/ddi0602/2025-09/Base-Instructions/ISB--Instruction-synchronization-barrier-
+    /// [FENCE.I]: https://docs.riscv.org/reference/isa/unpriv/zifencei.html
+    /// [CPUID]: https://www.felixcloutier.com/x86/cpuid
+    /// [SERIALIZE]: https://www.felixcloutier.com/x86/serialize
+    #[doc(alias = "isb")]
+    #[doc(alias = "isync")]
+    #[doc(alias = "sync_core")]
+    Instruction = 7,
+}
+
+impl BarrierKind {
+    /// Returns all barrier kinds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mem_barrier::{BarrierKind, BarrierType, mem_barrier};
+    ///
+    /// for &kind in BarrierKind::all() {
+    ///     for &ty in BarrierType::all() {
+    ///         mem_barrier(kind, ty);
+    ///     }
+    /// }
+    /// ```
+    pub const fn all() -> &'static [Self] {
+        &[
+            Self::Mmio,
+            Self::Smp,
+            Self::Virt,
+            Self::Dma,
+            Self::DmaCompletion,
+            Self::System,
+            Self::NonShareable,
+            Self::Device,
+            Self::Compiler,
+            Self::Instruction,
+        ]
+    }
+
+    /// Combines the requirements of two barrier kinds.
+    ///
+    /// This returns the weakest barrier kind that enforces the ordering of both `self` and `other`.
+    /// See the [`PartialOrd`] implementation for how barrier kinds compare by strength.
+    ///
+    /// If neither kind is stronger than the other, this returns [`Mmio`](Self::Mmio), which is stronger than all other data barriers.
+    /// [`Instruction`](Self::Instruction) barriers are not data barriers, though.
+    /// If only one of both kinds is an instruction barrier, no single barrier kind enforces both, so this returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mem_barrier::BarrierKind;
+    ///
+    /// assert_eq!(
+    ///     BarrierKind::Smp.combine(BarrierKind::Dma),
+    ///     Some(BarrierKind::Dma)
+    /// );
+    /// assert_eq!(
+    ///     BarrierKind::Dma.combine(BarrierKind::Device),
+    ///     Some(BarrierKind::Mmio)
+    /// );
+    /// assert_eq!(BarrierKind::Smp.combine(BarrierKind::Instruction), None);
+    /// ```
+    #[must_use]
+    pub const fn combine(self, other: Self) -> Option<Self> {
+        if self.implies(other) {
+            Some(self)
+        } else if other.implies(self) {
+            Some(other)
+        } else if matches!(self, Self::Instruction) || matches!(other, Self::Instruction) {
+            None
+        } else {
+            Some(Self::Mmio)
+        }
+    }
+
+    /// Returns `true` if `self` enforces at least the ordering of `other`.
+    const fn implies(self, other: Self) -> bool {
+        match self {
+            Self::Mmio => !matches!(other, Self::Instruction),
+            Self::DmaCompletion => matches!(
+                other,
+                Self::DmaCompletion
+                    | Self::Dma
+                    | Self::Virt
+                    | Self::Smp
+                    | Self::NonShareable
+                    | Self::Compiler
+            ),
+            Self::System => matches!(
+                other,
+                Self::System
+                    | Self::Dma
+                    | Self::Virt
+                    | Self::Smp
+                    | Self::NonShareable
+                    | Self::Compiler
+            ),
+            Self::Dma => matches!(
+                other,
+                Self::Dma | Self::Virt | Self::Smp | Self::NonShareable | Self::Compiler
+            ),
+            Self::Virt => matches!(
+                other,
+                Self::Virt | Self::Smp | Self::NonShareable | Self::Compiler
+            ),
+            Self::Smp => matches!(other, Self::Smp | Self::NonShareable | Self::Compiler),
+            Self::NonShareable => matches!(other, Self::NonShareable | Self::Compiler),
+            Self::Device => matches!(other, Self::Device | Self::Compiler),
+            Self::Instruction => matches!(other, Self::Instruction | Self::Compiler),
+            Self::Compiler => matches!(other, Self::Compiler),
+        }
+    }
+}
+
+/// Compares barrier kinds by strength.
+///
+/// A barrier kind is greater than another if it enforces at least the same ordering.
+/// Every barrier is also a compiler barrier, so [`Compiler`](BarrierKind::Compiler) is the weakest kind.
+/// The data barriers form the chain [`NonShareable`](BarrierKind::NonShareable) < [`Smp`](BarrierKind::Smp) < [`Virt`](BarrierKind::Virt) < [`Dma`](BarrierKind::Dma) < [`DmaCompletion`](BarrierKind::DmaCompletion) < [`Mmio`](BarrierKind::Mmio), since each one extends the set of observers of the previous one.
+/// [`System`](BarrierKind::System) barriers are greater than [`Dma`](BarrierKind::Dma) and less than [`Mmio`](BarrierKind::Mmio) barriers but incomparable to [`DmaCompletion`](BarrierKind::DmaCompletion), which only waits for the outer shareable domain.
+/// [`Device`](BarrierKind::Device) barriers only order device I/O, so they are only less than [`Mmio`](BarrierKind::Mmio) and incomparable to the other data barriers.
+/// [`Instruction`](BarrierKind::Instruction) barriers do not order data memory accesses, so they are incomparable to all data barriers.
+///
+/// Since some barrier kinds are incomparable, this does not implement [`Ord`].
+/// See [`BarrierKind::combine`] for combining the requirements of two barrier kinds.
+impl PartialOrd for BarrierKind {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        if self == other {
+            Some(cmp::Ordering::Equal)
+        } else if self.implies(*other) {
+            Some(cmp::Ordering::Greater)
+        } else if other.implies(*self) {
+            Some(cmp::Ordering::Less)
+        } else {
+            None
+        }
+    }
+}
+
+impl TryFrom<u8> for BarrierKind {
+    type Error = TryFromBarrierError;
+
+    /// Decodes a barrier kind from its discriminant.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Mmio),
+            1 => Ok(Self::Smp),
+            2 => Ok(Self::Dma),
+            3 => Ok(Self::DmaCompletion),
+            4 => Ok(Self::NonShareable),
+            5 => Ok(Self::Device),
+            6 => Ok(Self::Compiler),
+            7 => Ok(Self::Instruction),
+            8 => Ok(Self::Virt),
+            9 => Ok(Self::System),
+            _ => Err(TryFromBarrierError(())),
+        }
+    }
+}
+
+impl fmt::Display for BarrierKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Mmio => "mmio",
+            Self::Smp => "smp",
+            Self::Virt => "virt",
+            Self::Dma => "dma",
+            Self::DmaCompletion => "dma_completion",
+            Self::System => "system",
+            Self::NonShareable => "non_shareable",
+            Self::Device => "device",
+            Self::Compiler => "compiler",
+            Self::Instruction => "instruction",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for BarrierKind {
+    type Err = ParseBarrierError;
+
+    /// Parses a barrier kind from its [`Display`](fmt::Display) representation, such as `smp`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mmio" => Ok(Self::Mmio),
+            "smp" => Ok(Self::Smp),
+            "virt" => Ok(Self::Virt),
+            "dma" => Ok(Self::Dma),
+            "dma_completion" => Ok(Self::DmaCompletion),
+            "system" => Ok(Self::System),
+            "non_shareable" => Ok(Self::NonShareable),
+            "device" => Ok(Self::Device),
+            "compiler" => Ok(Self::Compiler),
+            "instruction" => Ok(Self::Instruction),
+            _ => Err(ParseBarrierError(())),
+        }
+    }
 }
 
 /// The type of a memory barrier.
 ///
 /// This enum determines which type of memory accesses are ordered: read, write, or both (general).
+///
+/// # Representation
+///
+/// This enum is represented as a `u8`.
+/// The discriminants of existing variants are stable and new variants receive new discriminants, which allows encoding barrier types as a single byte.
+/// Use [`TryFrom<u8>`] to decode them.
+///
+/// # Current implementation
+///
+/// On x86 without SSE2, every type of barrier that is not a compiler barrier runs a locked [ADD] instruction on the stack.
+/// If SSE2 is not enabled at build time, `x86::mem_barrier_with` runs fences for MMIO and DMA barriers if SSE2 was detected at runtime instead.
+///
+/// [ADD]: https://www.felixcloutier.com/x86/add
 #[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "nightly", derive(core::marker::ConstParamTy))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 #[non_exhaustive]
+#[repr(u8)]
 pub enum BarrierType {
     /// General.
     ///
@@ -221,14 +757,16 @@ pub enum BarrierType {
     /// # Current implementation
     ///
     /// On x86, this runs an [MFENCE] instruction.
+    /// With the `x86-lock-fence` feature, SMP barriers run a locked [ADD] instruction on the stack instead.
     ///
     /// [MFENCE]: https://www.felixcloutier.com/x86/mfence
+    /// [ADD]: https://www.felixcloutier.com/x86/add
     #[doc(alias = "mb")]
     #[doc(alias = "smp_mb")]
     #[doc(alias = "virt_mb")]
     #[doc(alias = "dma_mb")]
     #[default]
-    General,
+    General = 0,
 
     /// Read.
     ///
@@ -240,14 +778,14 @@ pub enum BarrierType {
     ///
     /// # Current implementation
     ///
-    /// On x86, this runs an [LFENCE] instruction.
+    /// On x86, this runs an [LFENCE] instruction, except for SMP barriers.
     ///
     /// [LFENCE]: https://www.felixcloutier.com/x86/lfence
     #[doc(alias = "rmb")]
     #[doc(alias = "smp_rmb")]
     #[doc(alias = "virt_rmb")]
     #[doc(alias = "dma_rmb")]
-    Read,
+    Read = 1,
 
     /// Write.
     ///
@@ -255,16 +793,322 @@ pub enum BarrierType {
     ///
     /// # Current implementation
     ///
-    /// On x86, this runs an [SFENCE] instruction.
+    /// On x86, this runs an [SFENCE] instruction, except for SMP barriers.
     ///
     /// [SFENCE]: https://www.felixcloutier.com/x86/sfence
     #[doc(alias = "wmb")]
     #[doc(alias = "smp_wmb")]
     #[doc(alias = "virt_wmb")]
     #[doc(alias = "dma_wmb")]
-    Write,
+    Write = 2,
+
+    /// Acquire.
+    ///
+    /// This type of barrier orders earlier reads before later reads and writes.
+    /// Placed after a read, it gives that read acquire semantics.
+    ///
+    /// # Current implementation
+    ///
+    /// On AArch64, this runs a barrier instruction with a load-only option, such as `DMB ISHLD`.
+    /// On RISC-V, this runs a `FENCE R, RW` instruction, extended to device I/O for MMIO barriers.
+    /// On x86, this is the same as [`General`](Self::General), except for SMP barriers, which are compiler barriers only.
+    Acquire = 3,
+
+    /// Release.
+    ///
+    /// This type of barrier orders earlier reads and writes before later writes.
+    /// Placed before a write, it gives that write release semantics.
+    ///
+    /// # Current implementation
+    ///
+    /// On AArch64, this is the same as [`General`](Self::General), since store-only options do not order earlier reads.
+    /// On RISC-V, this runs a `FENCE RW, W` instruction, extended to device I/O for MMIO barriers.
+    /// On x86, this is the same as [`General`](Self::General), except for SMP barriers, which are compiler barriers only.
+    Release = 4,
+}
+
+impl BarrierType {
+    /// Returns all barrier types.
+    ///
+    /// See [`BarrierKind::all`] for an example.
+    pub const fn all() -> &'static [Self] {
+        &[
+            Self::General,
+            Self::Read,
+            Self::Write,
+            Self::Acquire,
+            Self::Release,
+        ]
+    }
+
+    const fn from_ordering(ordering: Ordering) -> Self {
+        match ordering {
+            Ordering::Acquire => Self::Acquire,
+            Ordering::Release => Self::Release,
+            _ => Self::General,
+        }
+    }
+
+    /// Returns the accesses that this type of barrier orders.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mem_barrier::{AccessOrdering, AccessSet, BarrierType};
+    ///
+    /// assert_eq!(
+    ///     BarrierType::Acquire.ordering(),
+    ///     AccessOrdering::new(AccessSet::READ, AccessSet::all())
+    /// );
+    /// ```
+    pub const fn ordering(self) -> AccessOrdering {
+        let all = AccessSet::all();
+        match self {
+            Self::General => AccessOrdering::new(all, all),
+            Self::Read => AccessOrdering::new(AccessSet::READ, AccessSet::READ),
+            Self::Write => AccessOrdering::new(AccessSet::WRITE, AccessSet::WRITE),
+            Self::Acquire => AccessOrdering::new(AccessSet::READ, all),
+            Self::Release => AccessOrdering::new(all, AccessSet::WRITE),
+        }
+    }
+}
+
+impl From<Ordering> for BarrierType {
+    /// Converts an atomic memory ordering to the barrier type that enforces it.
+    ///
+    /// [`Acquire`](Ordering::Acquire) and [`Release`](Ordering::Release) map to [`Acquire`](Self::Acquire) and [`Release`](Self::Release).
+    /// All other orderings, including [`Relaxed`](Ordering::Relaxed) and orderings added in the future, map to [`General`](Self::General).
+    #[inline]
+    fn from(ordering: Ordering) -> Self {
+        Self::from_ordering(ordering)
+    }
+}
+
+impl TryFrom<u8> for BarrierType {
+    type Error = TryFromBarrierError;
+
+    /// Decodes a barrier type from its discriminant.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::General),
+            1 => Ok(Self::Read),
+            2 => Ok(Self::Write),
+            3 => Ok(Self::Acquire),
+            4 => Ok(Self::Release),
+            _ => Err(TryFromBarrierError(())),
+        }
+    }
+}
+
+impl fmt::Display for BarrierType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::General => "general",
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Acquire => "acquire",
+            Self::Release => "release",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for BarrierType {
+    type Err = ParseBarrierError;
+
+    /// Parses a barrier type from its [`Display`](fmt::Display) representation, such as `read`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "general" => Ok(Self::General),
+            "read" => Ok(Self::Read),
+            "write" => Ok(Self::Write),
+            "acquire" => Ok(Self::Acquire),
+            "release" => Ok(Self::Release),
+            _ => Err(ParseBarrierError(())),
+        }
+    }
+}
+
+/// A set of memory accesses ordered by an [`AccessOrdering`].
+///
+/// Sets can be combined with `|`.
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+pub struct AccessSet(u8);
+
+impl AccessSet {
+    /// Memory reads.
+    pub const READ: Self = Self(0b01);
+
+    /// Memory writes.
+    pub const WRITE: Self = Self(0b10);
+
+    /// Returns an empty set.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns the set of all memory accesses.
+    #[inline]
+    pub const fn all() -> Self {
+        Self(0b11)
+    }
+
+    /// Returns `true` if this set is empty.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns `true` if this set contains all accesses of `other`.
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the union of both sets.
+    #[inline]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOr for AccessSet {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl BitOrAssign for AccessSet {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = self.union(rhs);
+    }
+}
+
+impl fmt::Debug for AccessSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AccessSet(")?;
+        if self.is_empty() {
+            f.write_str("empty")?;
+        }
+        for (set, c) in [(Self::READ, 'r'), (Self::WRITE, 'w')] {
+            if self.contains(set) {
+                fmt::Write::write_char(f, c)?;
+            }
+        }
+        f.write_str(")")
+    }
+}
+
+/// The memory accesses ordered by a memory barrier.
+///
+/// A barrier with this ordering orders all earlier accesses in `before` before all later accesses in `after`.
+/// This can express orderings that no [`BarrierType`] describes exactly, such as ordering earlier reads before later writes only.
+/// Each barrier type corresponds to an ordering, see [`BarrierType::ordering`].
+///
+/// Use [`mem_barrier_ordered`] to run a barrier with this ordering.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct AccessOrdering {
+    /// The earlier accesses.
+    pub before: AccessSet,
+
+    /// The later accesses.
+    pub after: AccessSet,
+}
+
+impl AccessOrdering {
+    /// Creates a new ordering of earlier accesses in `before` before later accesses in `after`.
+    #[inline]
+    pub const fn new(before: AccessSet, after: AccessSet) -> Self {
+        Self { before, after }
+    }
+
+    /// Returns the weakest barrier type that enforces this ordering.
+    ///
+    /// If either set is empty, this returns [`BarrierType::General`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mem_barrier::{AccessOrdering, AccessSet, BarrierType};
+    ///
+    /// let ordering = AccessOrdering::new(AccessSet::READ, AccessSet::WRITE);
+    /// assert_eq!(ordering.barrier_type(), BarrierType::Acquire);
+    /// ```
+    pub const fn barrier_type(self) -> BarrierType {
+        let (before, after) = (self.before.0, self.after.0);
+        if before == 0 || after == 0 {
+            BarrierType::General
+        } else if before == AccessSet::READ.0 && after == AccessSet::READ.0 {
+            BarrierType::Read
+        } else if before == AccessSet::WRITE.0 && after == AccessSet::WRITE.0 {
+            BarrierType::Write
+        } else if before == AccessSet::READ.0 {
+            BarrierType::Acquire
+        } else if after == AccessSet::WRITE.0 {
+            BarrierType::Release
+        } else {
+            BarrierType::General
+        }
+    }
+}
+
+impl From<BarrierType> for AccessOrdering {
+    #[inline]
+    fn from(ty: BarrierType) -> Self {
+        ty.ordering()
+    }
+}
+
+/// An error which can be returned when parsing a [`BarrierKind`] or [`BarrierType`].
+///
+/// # Examples
+///
+/// ```
+/// use mem_barrier::BarrierKind;
+///
+/// assert_eq!("smp".parse(), Ok(BarrierKind::Smp));
+/// assert!("rmb".parse::<BarrierKind>().is_err());
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ParseBarrierError(());
+
+impl fmt::Display for ParseBarrierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("unknown memory barrier name")
+    }
+}
+
+impl core::error::Error for ParseBarrierError {}
+
+/// An error which can be returned when decoding a [`BarrierKind`] or [`BarrierType`] from a `u8`.
+///
+/// # Examples
+///
+/// ```
+/// use mem_barrier::BarrierType;
+///
+/// assert_eq!(
+///     BarrierType::try_from(BarrierType::Write as u8),
+///     Ok(BarrierType::Write)
+/// );
+/// assert!(BarrierType::try_from(u8::MAX).is_err());
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct TryFromBarrierError(());
+
+impl fmt::Display for TryFromBarrierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("unknown memory barrier discriminant")
+    }
 }
 
+impl core::error::Error for TryFromBarrierError {}
+
 /// A memory barrier.
 ///
 /// This function runs the appropriate CPU instructions for enforcing memory ordering according to the provided [`BarrierKind`] and [`BarrierType`].
@@ -274,44 +1118,1976 @@ pub enum BarrierType {
 /// On RISC-V, this runs a [FENCE] instruction.
 ///
 /// [FENCE]: https://docs.riscv.org/reference/isa/unpriv/rv32.html#fence
-#[inline]
+#[inline(always)]
 pub fn mem_barrier(kind: BarrierKind, ty: BarrierType) {
-    let cpu_barrier_kind = match kind {
-        BarrierKind::Mmio => arch::CpuBarrierKind::Mmio,
-        BarrierKind::Smp => arch::CpuBarrierKind::Smp,
-        BarrierKind::Dma => arch::CpuBarrierKind::Dma,
-        BarrierKind::Compiler => {
-            compiler_barrier();
-            return;
-        }
-    };
-
-    arch::mem_barrier(cpu_barrier_kind, ty);
-}
+    #[cfg(feature = "trace")]
+    trace(kind, ty);
 
-#[inline]
-fn compiler_barrier() {
-    // SAFETY: This asm invocation is empty.
-    unsafe {
-        core::arch::asm!("", options(preserves_flags, nostack));
+    match barrier(kind) {
+        ArchBarrier::Cpu(kind) => arch::mem_barrier(kind, ty),
+        ArchBarrier::Compiler => arch::compiler_fence(ty),
+        ArchBarrier::Instruction => arch::instruction_barrier(),
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// A memory barrier that always runs the inline assembly implementation.
+///
+/// This is the same as [`mem_barrier`](fn@mem_barrier), but ignores the `stdarch` feature, so that it never uses [`core::arch`] intrinsics.
+/// This allows comparing both implementations within one build, for example, when chasing down a miscompilation.
+/// On targets without an intrinsic implementation, this is the same as [`mem_barrier`](fn@mem_barrier).
+///
+/// # Examples
+///
+/// ```
+/// use mem_barrier::{BarrierKind, BarrierType, mem_barrier, mem_barrier_asm};
+///
+/// mem_barrier(BarrierKind::Mmio, BarrierType::Write);
+/// mem_barrier_asm(BarrierKind::Mmio, BarrierType::Write);
+/// ```
+#[inline(always)]
+pub fn mem_barrier_asm(kind: BarrierKind, ty: BarrierType) {
+    #[cfg(feature = "trace")]
+    trace(kind, ty);
+
+    match barrier(kind) {
+        ArchBarrier::Cpu(kind) => arch::mem_barrier_asm(kind, ty),
+        ArchBarrier::Compiler => arch::compiler_fence(ty),
+        ArchBarrier::Instruction => arch::instruction_barrier_asm(),
+    }
+}
+
+/// A memory barrier with an arbitrary [`AccessOrdering`].
+///
+/// On RISC-V, this runs a `FENCE` instruction with exactly the requested predecessor and successor sets, extended to device I/O like [`mem_barrier`](fn@mem_barrier).
+/// On other architectures, this is the same as `mem_barrier(kind, ordering.barrier_type())`, which rounds up to the nearest supported barrier.
+/// The trace hook receives that barrier type as well.
+///
+/// # Examples
+///
+/// ```
+/// use mem_barrier::{AccessOrdering, AccessSet, BarrierKind, mem_barrier_ordered};
+///
+/// // Order earlier reads before later writes, but not earlier writes before later reads.
+/// // On RISC-V, this runs `fence r, w`.
+/// let ordering = AccessOrdering::new(AccessSet::READ, AccessSet::WRITE);
+/// mem_barrier_ordered(BarrierKind::Smp, ordering);
+/// ```
+#[inline(always)]
+pub fn mem_barrier_ordered(kind: BarrierKind, ordering: AccessOrdering) {
+    #[cfg(feature = "trace")]
+    trace(kind, ordering.barrier_type());
+
+    match barrier(kind) {
+        ArchBarrier::Cpu(kind) => arch::mem_barrier_ordered(kind, ordering),
+        ArchBarrier::Compiler => arch::compiler_fence(ordering.barrier_type()),
+        ArchBarrier::Instruction => arch::instruction_barrier(),
+    }
+}
+
+/// A barrier kind known at the type level.
+///
+/// This allows parameterizing code by a barrier kind statically, which guarantees that the barrier selection is resolved at compile time.
+///
+/// # Examples
+///
+/// ```
+/// use mem_barrier::{BarrierType, Kind, Smp};
+///
+/// struct Ring<K: Kind> {
+///     _kind: K,
+/// }
+///
+/// impl<K: Kind> Ring<K> {
+///     fn publish(&self) {
+///         K::barrier(BarrierType::Write);
+///     }
+/// }
+///
+/// let ring = Ring { _kind: Smp };
+/// ring.publish();
+/// ```
+pub trait Kind {
+    /// The corresponding barrier kind.
+    const KIND: BarrierKind;
+
+    /// A memory barrier of this kind.
+    ///
+    /// This is the same as `mem_barrier(Self::KIND, ty)`.
+    fn barrier(ty: BarrierType);
+}
+
+/// The [`Mmio`](BarrierKind::Mmio) barrier kind at the type level.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Mmio;
+
+impl Kind for Mmio {
+    const KIND: BarrierKind = BarrierKind::Mmio;
+
+    #[inline(always)]
+    fn barrier(ty: BarrierType) {
+        mem_barrier(Self::KIND, ty);
+    }
+}
+
+/// The [`Smp`](BarrierKind::Smp) barrier kind at the type level.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Smp;
+
+impl Kind for Smp {
+    const KIND: BarrierKind = BarrierKind::Smp;
+
+    #[inline(always)]
+    fn barrier(ty: BarrierType) {
+        mem_barrier(Self::KIND, ty);
+    }
+}
+
+/// The [`Dma`](BarrierKind::Dma) barrier kind at the type level.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Dma;
+
+impl Kind for Dma {
+    const KIND: BarrierKind = BarrierKind::Dma;
+
+    #[inline(always)]
+    fn barrier(ty: BarrierType) {
+        mem_barrier(Self::KIND, ty);
+    }
+}
+
+macro_rules! barrier_consts {
+    ($($name:ident = $kind:ident, $ty:ident;)*) => {
+        $(
+            #[doc = concat!(
+                "A memory barrier of kind [`", stringify!($kind), "`](BarrierKind::", stringify!($kind),
+                ") and type [`", stringify!($ty), "`](BarrierType::", stringify!($ty), ")."
+            )]
+            pub const $name: Self = Self {
+                kind: BarrierKind::$kind,
+                ty: BarrierType::$ty,
+            };
+        )*
+    };
+}
+
+/// A memory barrier builder.
+///
+/// This collects the [`BarrierKind`] and [`BarrierType`] of a memory barrier.
+/// Unset arguments default to [`BarrierKind::default()`] and [`BarrierType::default()`], which are [`BarrierKind::Mmio`] and [`BarrierType::General`].
+///
+/// # Examples
+///
+/// ```
+/// use mem_barrier::{Barrier, BarrierKind, BarrierType};
+///
+/// // mem_barrier(BarrierKind::Mmio, BarrierType::General)
+/// Barrier::new().execute();
+/// // mem_barrier(BarrierKind::Dma, BarrierType::Write)
+/// Barrier::new()
+///     .kind(BarrierKind::Dma)
+///     .ty(BarrierType::Write)
+///     .execute();
+/// ```
+///
+/// There are also constants for every combination of barrier kind and type:
+///
+/// ```
+/// use mem_barrier::Barrier;
+///
+/// // Barriers to run before starting each device.
+/// const DEVICE_BARRIERS: [Barrier; 2] = [Barrier::MMIO_WRITE, Barrier::DMA_RELEASE];
+///
+/// DEVICE_BARRIERS[1].execute();
+/// ```
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+#[must_use]
+pub struct Barrier {
+    kind: BarrierKind,
+    ty: BarrierType,
+}
+
+impl Barrier {
+    barrier_consts! {
+        MMIO_GENERAL = Mmio, General;
+        MMIO_READ = Mmio, Read;
+        MMIO_WRITE = Mmio, Write;
+        MMIO_ACQUIRE = Mmio, Acquire;
+        MMIO_RELEASE = Mmio, Release;
+        SMP_GENERAL = Smp, General;
+        SMP_READ = Smp, Read;
+        SMP_WRITE = Smp, Write;
+        SMP_ACQUIRE = Smp, Acquire;
+        SMP_RELEASE = Smp, Release;
+        VIRT_GENERAL = Virt, General;
+        VIRT_READ = Virt, Read;
+        VIRT_WRITE = Virt, Write;
+        VIRT_ACQUIRE = Virt, Acquire;
+        VIRT_RELEASE = Virt, Release;
+        DMA_GENERAL = Dma, General;
+        DMA_READ = Dma, Read;
+        DMA_WRITE = Dma, Write;
+        DMA_ACQUIRE = Dma, Acquire;
+        DMA_RELEASE = Dma, Release;
+        DMA_COMPLETION_GENERAL = DmaCompletion, General;
+        DMA_COMPLETION_READ = DmaCompletion, Read;
+        DMA_COMPLETION_WRITE = DmaCompletion, Write;
+        DMA_COMPLETION_ACQUIRE = DmaCompletion, Acquire;
+        DMA_COMPLETION_RELEASE = DmaCompletion, Release;
+        SYSTEM_GENERAL = System, General;
+        SYSTEM_READ = System, Read;
+        SYSTEM_WRITE = System, Write;
+        SYSTEM_ACQUIRE = System, Acquire;
+        SYSTEM_RELEASE = System, Release;
+        NON_SHAREABLE_GENERAL = NonShareable, General;
+        NON_SHAREABLE_READ = NonShareable, Read;
+        NON_SHAREABLE_WRITE = NonShareable, Write;
+        NON_SHAREABLE_ACQUIRE = NonShareable, Acquire;
+        NON_SHAREABLE_RELEASE = NonShareable, Release;
+        DEVICE_GENERAL = Device, General;
+        DEVICE_READ = Device, Read;
+        DEVICE_WRITE = Device, Write;
+        DEVICE_ACQUIRE = Device, Acquire;
+        DEVICE_RELEASE = Device, Release;
+        COMPILER_GENERAL = Compiler, General;
+        COMPILER_READ = Compiler, Read;
+        COMPILER_WRITE = Compiler, Write;
+        COMPILER_ACQUIRE = Compiler, Acquire;
+        COMPILER_RELEASE = Compiler, Release;
+        INSTRUCTION_GENERAL = Instruction, General;
+        INSTRUCTION_READ = Instruction, Read;
+        INSTRUCTION_WRITE = Instruction, Write;
+        INSTRUCTION_ACQUIRE = Instruction, Acquire;
+        INSTRUCTION_RELEASE = Instruction, Release;
+    }
+
+    /// Creates a new memory barrier builder with default arguments.
+    pub const fn new() -> Self {
+        Self {
+            kind: BarrierKind::Mmio,
+            ty: BarrierType::General,
+        }
+    }
+
+    /// Creates the weakest memory barrier of the given kind that enforces an atomic memory ordering.
+    ///
+    /// The barrier kind describes the scope of the ordering, such as [`BarrierKind::Smp`] for ordering against other CPUs or [`BarrierKind::Compiler`] for ordering within a single CPU.
+    /// See [`BarrierType::from`] for how orderings map to barrier types.
+    /// [`Ordering::Relaxed`] does not order anything, so it creates a compiler barrier regardless of the kind.
+    /// This is the barrier that [`mem_barrier_for`] runs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::sync::atomic::Ordering;
+    ///
+    /// use mem_barrier::{Barrier, BarrierKind};
+    ///
+    /// let barrier = Barrier::for_ordering(BarrierKind::Smp, Ordering::Acquire);
+    /// assert_eq!(barrier, Barrier::SMP_ACQUIRE);
+    ///
+    /// if cfg!(target_arch = "aarch64") {
+    ///     assert_eq!(barrier.mnemonic(), "dmb ishld");
+    /// }
+    /// if cfg!(target_arch = "x86_64") {
+    ///     assert!(barrier.is_noop());
+    /// }
+    ///
+    /// barrier.execute();
+    ///
+    /// let barrier = Barrier::for_ordering(BarrierKind::Mmio, Ordering::Relaxed);
+    /// assert_eq!(barrier, Barrier::new().kind(BarrierKind::Compiler));
+    /// ```
+    #[doc(alias = "minimal_barrier")]
+    pub const fn for_ordering(kind: BarrierKind, ordering: Ordering) -> Self {
+        let kind = match ordering {
+            Ordering::Relaxed => BarrierKind::Compiler,
+            _ => kind,
+        };
+        Self {
+            kind,
+            ty: BarrierType::from_ordering(ordering),
+        }
+    }
+
+    /// Sets the [`BarrierKind`].
+    pub const fn kind(self, kind: BarrierKind) -> Self {
+        Self { kind, ..self }
+    }
+
+    /// Sets the [`BarrierType`].
+    pub const fn ty(self, ty: BarrierType) -> Self {
+        Self { ty, ..self }
+    }
+
+    /// Runs the memory barrier.
+    ///
+    /// This is the same as [`mem_barrier`](fn@mem_barrier).
+    #[inline(always)]
+    pub fn execute(self) {
+        mem_barrier(self.kind, self.ty);
+    }
+
+    /// Returns the assembly of the memory barrier for the current target.
+    ///
+    /// This is the same as [`barrier_mnemonic`].
+    pub const fn mnemonic(self) -> &'static str {
+        barrier_mnemonic(self.kind, self.ty)
+    }
+
+    /// Returns `true` if the memory barrier does not run any CPU instructions on the current target.
+    ///
+    /// This is the same as [`is_noop`].
+    pub const fn is_noop(self) -> bool {
+        is_noop(self.kind, self.ty)
+    }
+}
+
+/// A memory barrier for C.
+///
+/// This decodes `kind` and `ty` using [`TryFrom<u8>`] and calls [`mem_barrier`](fn@mem_barrier).
+/// Invalid values fall back to the strongest barrier: [`BarrierKind::Mmio`] and [`BarrierType::General`], respectively.
+///
+/// This function requires the `ffi` feature.
+/// See the `ffi` example for the corresponding C declarations.
+#[cfg(feature = "ffi")]
+#[unsafe(no_mangle)]
+pub extern "C" fn mem_barrier_ffi(kind: u8, ty: u8) {
+    let kind = BarrierKind::try_from(kind).unwrap_or(BarrierKind::Mmio);
+    let ty = BarrierType::try_from(ty).unwrap_or(BarrierType::General);
+    mem_barrier(kind, ty);
+}
+
+/// A memory barrier for an atomic memory ordering.
+///
+/// This is the same as `mem_barrier(kind, BarrierType::from(ordering))`, except that [`Ordering::Relaxed`] only runs a compiler barrier.
+/// See [`BarrierType::from`] for how orderings map to barrier types.
+/// To inspect the barrier before running it, use [`Barrier::for_ordering`].
+///
+/// # Examples
+///
+/// ```
+/// use core::sync::atomic::Ordering;
+///
+/// use mem_barrier::{BarrierKind, mem_barrier_for};
+///
+/// // mem_barrier(BarrierKind::Smp, BarrierType::Acquire)
+/// mem_barrier_for(BarrierKind::Smp, Ordering::Acquire);
+/// ```
+#[doc(alias = "minimal_barrier")]
+#[inline(always)]
+pub fn mem_barrier_for(kind: BarrierKind, ordering: Ordering) {
+    Barrier::for_ordering(kind, ordering).execute();
+}
+
+/// A general memory barrier.
+///
+/// This is the same as `mem_barrier(kind, BarrierType::General)`.
+#[inline(always)]
+pub fn full_barrier(kind: BarrierKind) {
+    mem_barrier(kind, BarrierType::General);
+}
+
+/// A read memory barrier.
+///
+/// This is the same as `mem_barrier(kind, BarrierType::Read)`.
+#[inline(always)]
+pub fn read_barrier(kind: BarrierKind) {
+    mem_barrier(kind, BarrierType::Read);
+}
+
+/// A write memory barrier.
+///
+/// This is the same as `mem_barrier(kind, BarrierType::Write)`.
+#[inline(always)]
+pub fn write_barrier(kind: BarrierKind) {
+    mem_barrier(kind, BarrierType::Write);
+}
+
+#[cfg(feature = "trace")]
+static TRACE_HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Sets the trace hook.
+///
+/// The trace hook is called with the barrier kind and type at the start of every [`mem_barrier`](fn@mem_barrier) call.
+/// Most other functions of this crate, such as [`smp_mb`], [`Kind::barrier`], and `aarch64::isb`, call [`mem_barrier`](fn@mem_barrier) as well.
+/// `x86::mem_barrier_with` calls the hook too.
+/// Barriers without a corresponding [`BarrierKind`], such as [`full_system_barrier`], `aarch64::dsb`, or `riscv::fence`, do not call the hook.
+/// Passing `None` removes the hook.
+///
+/// The hook must not run memory barriers from this crate itself, since that would call the hook again and recurse indefinitely.
+///
+/// This function requires the `trace` feature.
+///
+/// # Examples
+///
+/// ```
+/// use core::sync::atomic::{AtomicUsize, Ordering};
+///
+/// use mem_barrier::{BarrierKind, BarrierType, mem_barrier, set_trace_hook};
+///
+/// static COUNT: AtomicUsize = AtomicUsize::new(0);
+///
+/// fn hook(_kind: BarrierKind, _ty: BarrierType) {
+///     COUNT.fetch_add(1, Ordering::Relaxed);
+/// }
+///
+/// set_trace_hook(Some(hook));
+/// mem_barrier(BarrierKind::Smp, BarrierType::General);
+/// set_trace_hook(None);
+/// # assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+/// ```
+#[cfg(feature = "trace")]
+pub fn set_trace_hook(hook: Option<fn(BarrierKind, BarrierType)>) {
+    let hook = hook.map_or(ptr::null_mut(), |hook| hook as *mut ());
+    TRACE_HOOK.store(hook, Ordering::Release);
+}
+
+#[cfg(feature = "trace")]
+#[inline]
+fn trace(kind: BarrierKind, ty: BarrierType) {
+    let hook = TRACE_HOOK.load(Ordering::Acquire);
+    if !hook.is_null() {
+        // SAFETY: `set_trace_hook` only stores valid function pointers of this type.
+        let hook = unsafe { mem::transmute::<*mut (), fn(BarrierKind, BarrierType)>(hook) };
+        hook(kind, ty);
+    }
+}
+
+/// Returns the assembly of the memory barrier for the current target.
+///
+/// This function returns the CPU instructions that [`mem_barrier`](fn@mem_barrier) runs for the provided [`BarrierKind`] and [`BarrierType`] without running them.
+/// Register operands chosen by the compiler are written as `<Rt>`.
+/// If the barrier does not run any CPU instructions, such as a compiler barrier, this returns an empty string.
+/// As described for [`compiler_barrier`], compiler barriers run a full memory barrier on some architectures without the `nightly` feature, so this returns the mnemonic of that barrier instead.
+///
+/// On architectures that fall back to [`core::sync::atomic::fence`], the compiler chooses the instructions, so this returns `fence`.
+///
+/// The returned strings are informational and may change between releases.
+///
+/// # Examples
+///
+/// ```
+/// use mem_barrier::{BarrierKind, BarrierType, barrier_mnemonic};
+///
+/// if cfg!(target_arch = "x86_64") {
+///     assert_eq!(
+///         barrier_mnemonic(BarrierKind::Mmio, BarrierType::General),
+///         "mfence"
+///     );
+///     assert_eq!(barrier_mnemonic(BarrierKind::Smp, BarrierType::Read), "");
+/// }
+///
+/// assert_eq!(
+///     barrier_mnemonic(BarrierKind::Compiler, BarrierType::General),
+///     ""
+/// );
+/// ```
+pub const fn barrier_mnemonic(kind: BarrierKind, ty: BarrierType) -> &'static str {
+    match barrier(kind) {
+        ArchBarrier::Cpu(kind) => arch::mnemonic(kind, ty),
+        ArchBarrier::Compiler => arch::compiler_barrier_mnemonic(),
+        ArchBarrier::Instruction => arch::instruction_barrier_mnemonic(),
+    }
+}
+
+/// Returns `true` if the memory barrier does not run any CPU instructions on the current target.
+///
+/// Such barriers, including [`Compiler`](BarrierKind::Compiler) barriers on most targets, are compiler barriers only.
+/// This is the same as checking whether [`barrier_mnemonic`] returns an empty string.
+///
+/// # Examples
+///
+/// ```
+/// use mem_barrier::{BarrierKind, BarrierType, is_noop};
+///
+/// const _: () = assert!(is_noop(BarrierKind::Compiler, BarrierType::General));
+///
+/// if cfg!(target_arch = "x86_64") {
+///     assert!(is_noop(BarrierKind::Smp, BarrierType::Read));
+///     assert!(!is_noop(BarrierKind::Mmio, BarrierType::General));
+/// }
+/// ```
+pub const fn is_noop(kind: BarrierKind, ty: BarrierType) -> bool {
+    barrier_mnemonic(kind, ty).is_empty()
+}
+
+/// Returns `true` if the barrier type makes a difference for the barrier kind on the current target.
+///
+/// Many architectures do not implement every barrier type and round it up to a stronger barrier instead.
+/// This returns `false` if the barrier of type `ty` runs the same instructions as the [`General`](BarrierType::General) barrier of the same kind, which means that a weaker type was silently upgraded to a full barrier.
+/// [`General`](BarrierType::General) barriers always distinguish themselves.
+///
+/// This compares the instructions reported by [`barrier_mnemonic`].
+/// [`Compiler`](BarrierKind::Compiler) barriers run no instructions but select the ordering of their compiler fence by type, so they always distinguish barrier types.
+/// [`Instruction`](BarrierKind::Instruction) barriers ignore the barrier type, so they never distinguish it.
+/// On architectures that fall back to [`core::sync::atomic::fence`], the compiler chooses the instructions, so this returns `false` for all types other than [`General`](BarrierType::General).
+///
+/// # Examples
+///
+/// ```
+/// use mem_barrier::{BarrierKind, BarrierType, distinguishes};
+///
+/// const _: () = assert!(distinguishes(BarrierKind::Compiler, BarrierType::Acquire));
+///
+/// if cfg!(target_arch = "aarch64") {
+///     // AArch64 has load-only barriers but no release barriers.
+///     assert!(distinguishes(BarrierKind::Smp, BarrierType::Acquire));
+///     assert!(!distinguishes(BarrierKind::Smp, BarrierType::Release));
+/// }
+/// ```
+pub const fn distinguishes(kind: BarrierKind, ty: BarrierType) -> bool {
+    match barrier(kind) {
+        ArchBarrier::Compiler => true,
+        _ if matches!(ty, BarrierType::General) => true,
+        _ => !str_eq(
+            barrier_mnemonic(kind, ty),
+            barrier_mnemonic(kind, BarrierType::General),
+        ),
+    }
+}
+
+/// Returns `true` if both strings are equal.
+///
+/// This is [`str::eq`], which is not `const` yet.
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// The target architecture that this crate was compiled for.
+///
+/// This is the value of `target_arch`, such as `"aarch64"`.
+///
+/// # Examples
+///
+/// ```
+/// use mem_barrier::{ARCH, USES_STDARCH};
+///
+/// let implementation = if USES_STDARCH {
+///     "stdarch intrinsics"
+/// } else {
+///     "inline assembly"
+/// };
+/// println!("mem-barrier: {ARCH}, {implementation}");
+/// ```
+pub const ARCH: &str = env!("MEM_BARRIER_TARGET_ARCH");
+
+/// Whether memory barriers are implemented with [`core::arch`] intrinsics on the current target.
+///
+/// This depends on the target architecture and on the `stdarch` and `nightly` features.
+/// Otherwise, memory barriers are implemented with inline assembly or atomic fences.
+pub const USES_STDARCH: bool = arch::USES_STDARCH;
+
+/// Whether memory barriers are implemented with unstable, nightly-only [`core::arch`] intrinsics on the current target.
+///
+/// This implies [`USES_STDARCH`].
+pub const USES_NIGHTLY_INTRINSICS: bool = arch::USES_NIGHTLY_INTRINSICS;
+
+enum ArchBarrier {
+    Cpu(arch::CpuBarrierKind),
+    Compiler,
+    Instruction,
+}
+
+#[inline(always)]
+const fn barrier(kind: BarrierKind) -> ArchBarrier {
+    match kind {
+        BarrierKind::Mmio => ArchBarrier::Cpu(arch::CpuBarrierKind::Mmio),
+        #[cfg(not(feature = "single_core"))]
+        BarrierKind::Smp => ArchBarrier::Cpu(arch::CpuBarrierKind::Smp),
+        #[cfg(feature = "single_core")]
+        BarrierKind::Smp => ArchBarrier::Compiler,
+        BarrierKind::Virt => ArchBarrier::Cpu(arch::CpuBarrierKind::Smp),
+        BarrierKind::Dma => ArchBarrier::Cpu(arch::CpuBarrierKind::Dma),
+        #[cfg(target_arch = "aarch64")]
+        BarrierKind::DmaCompletion => ArchBarrier::Cpu(arch::CpuBarrierKind::DmaCompletion),
+        #[cfg(not(target_arch = "aarch64"))]
+        BarrierKind::DmaCompletion => ArchBarrier::Cpu(arch::CpuBarrierKind::Mmio),
+        #[cfg(target_arch = "aarch64")]
+        BarrierKind::System => ArchBarrier::Cpu(arch::CpuBarrierKind::System),
+        #[cfg(not(target_arch = "aarch64"))]
+        BarrierKind::System => ArchBarrier::Cpu(arch::CpuBarrierKind::Dma),
+        #[cfg(target_arch = "aarch64")]
+        BarrierKind::NonShareable => ArchBarrier::Cpu(arch::CpuBarrierKind::NonShareable),
+        #[cfg(not(target_arch = "aarch64"))]
+        BarrierKind::NonShareable => ArchBarrier::Cpu(arch::CpuBarrierKind::Smp),
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        BarrierKind::Device => ArchBarrier::Cpu(arch::CpuBarrierKind::Device),
+        #[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+        BarrierKind::Device => ArchBarrier::Cpu(arch::CpuBarrierKind::Dma),
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        BarrierKind::Device => ArchBarrier::Compiler,
+        #[cfg(not(any(
+            target_arch = "aarch64",
+            target_arch = "arm",
+            target_arch = "riscv32",
+            target_arch = "riscv64",
+            target_arch = "x86",
+            target_arch = "x86_64"
+        )))]
+        BarrierKind::Device => ArchBarrier::Cpu(arch::CpuBarrierKind::Mmio),
+        BarrierKind::Compiler => ArchBarrier::Compiler,
+        BarrierKind::Instruction => ArchBarrier::Instruction,
+    }
+}
+
+/// A memory barrier with compile-time arguments.
+///
+/// This is the same as [`mem_barrier`](fn@mem_barrier), but takes the [`BarrierKind`] and [`BarrierType`] as const generic arguments.
+/// Since this function is always inlined, the barrier selection is resolved at compile time, and this compiles to just the barrier instructions when optimizations are enabled.
+///
+/// This function requires the `nightly` feature.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "nightly")]
+/// # {
+/// use mem_barrier::{BarrierKind, BarrierType, mem_barrier_const};
+///
+/// mem_barrier_const::<{ BarrierKind::Smp }, { BarrierType::Write }>();
+/// # }
+/// ```
+#[cfg(feature = "nightly")]
+#[inline(always)]
+pub fn mem_barrier_const<const KIND: BarrierKind, const TYPE: BarrierType>() {
+    mem_barrier(KIND, TYPE);
+}
+
+/// A memory barrier with defaulted arguments.
+///
+/// This macro expands to a call to the [`mem_barrier`](fn@mem_barrier) function.
+/// Omitted arguments default to [`BarrierKind::default()`] and [`BarrierType::default()`], which are [`BarrierKind::Mmio`] and [`BarrierType::General`].
+///
+/// # Examples
+///
+/// ```
+/// use mem_barrier::mem_barrier;
+///
+/// // mem_barrier(BarrierKind::Mmio, BarrierType::General)
+/// mem_barrier!();
+/// // mem_barrier(BarrierKind::Smp, BarrierType::General)
+/// mem_barrier!(Smp);
+/// // mem_barrier(BarrierKind::Smp, BarrierType::Read)
+/// mem_barrier!(Smp, Read);
+/// ```
+#[macro_export]
+macro_rules! mem_barrier {
+    () => {
+        $crate::mem_barrier(
+            $crate::BarrierKind::default(),
+            $crate::BarrierType::default(),
+        )
+    };
+    ($kind:ident) => {
+        $crate::mem_barrier($crate::BarrierKind::$kind, $crate::BarrierType::default())
+    };
+    ($kind:ident, $ty:ident) => {
+        $crate::mem_barrier($crate::BarrierKind::$kind, $crate::BarrierType::$ty)
+    };
+}
+
+/// A general MMIO memory barrier.
+///
+/// This is the same as `mem_barrier(BarrierKind::Mmio, BarrierType::General)` and corresponds to the `mb` Linux function.
+#[inline(always)]
+pub fn mb() {
+    mem_barrier(BarrierKind::Mmio, BarrierType::General);
+}
+
+/// A read MMIO memory barrier.
+///
+/// This is the same as `mem_barrier(BarrierKind::Mmio, BarrierType::Read)` and corresponds to the `rmb` Linux function.
+#[inline(always)]
+pub fn rmb() {
+    mem_barrier(BarrierKind::Mmio, BarrierType::Read);
+}
+
+/// A write MMIO memory barrier.
+///
+/// This is the same as `mem_barrier(BarrierKind::Mmio, BarrierType::Write)` and corresponds to the `wmb` Linux function.
+#[inline(always)]
+pub fn wmb() {
+    mem_barrier(BarrierKind::Mmio, BarrierType::Write);
+}
+
+/// The strongest data memory barrier of the target.
+///
+/// This orders all memory accesses, including device I/O, as observed by every agent in the system.
+/// It implies every other [`BarrierKind`] except [`Instruction`](BarrierKind::Instruction), so it is a sledgehammer for when the right kind of barrier is not known, such as during early boot before the device memory attributes are set up.
+/// Once they are, prefer a weaker barrier, which is usually cheaper.
+///
+/// This does not depend on the [`BarrierKind`] abstraction, so it does not call the trace hook either.
+/// It currently runs the same instructions as [`mb`].
+///
+/// # Current implementation
+///
+/// On AArch64, this runs `DSB SY`.
+/// On RISC-V, this runs `FENCE IORW, IORW`.
+/// On x86, this runs `MFENCE`.
+/// On x86 processors without SSE2, this runs a locked `ADD` on the stack instead.
+///
+/// # Examples
+///
+/// ```
+/// use mem_barrier::full_system_barrier;
+///
+/// // Write to a device whose memory attributes are not known yet.
+///
+/// full_system_barrier();
+/// ```
+#[inline(always)]
+pub fn full_system_barrier() {
+    arch::mem_barrier(arch::CpuBarrierKind::Mmio, BarrierType::General);
+}
+
+/// A general SMP memory barrier.
+///
+/// This is the same as `mem_barrier(BarrierKind::Smp, BarrierType::General)` and corresponds to the `smp_mb` Linux function.
+///
+/// # Examples
+///
+/// ```
+/// use core::sync::atomic::{AtomicBool, Ordering};
+///
+/// use mem_barrier::smp_mb;
+///
+/// let flag = AtomicBool::new(false);
+/// let other_flag = AtomicBool::new(false);
+///
+/// flag.store(true, Ordering::Relaxed);
+/// // Order the store to `flag` before the load of `other_flag`.
+/// smp_mb();
+/// let other = other_flag.load(Ordering::Relaxed);
+/// # assert!(!other);
+/// ```
+#[inline(always)]
+pub fn smp_mb() {
+    mem_barrier(BarrierKind::Smp, BarrierType::General);
+}
+
+/// A read SMP memory barrier.
+///
+/// This is the same as `mem_barrier(BarrierKind::Smp, BarrierType::Read)` and corresponds to the `smp_rmb` Linux function.
+///
+/// See [`smp_wmb`] for an example.
+#[inline(always)]
+pub fn smp_rmb() {
+    mem_barrier(BarrierKind::Smp, BarrierType::Read);
+}
+
+/// A write SMP memory barrier.
+///
+/// This is the same as `mem_barrier(BarrierKind::Smp, BarrierType::Write)` and corresponds to the `smp_wmb` Linux function.
+///
+/// # Examples
+///
+/// ```
+/// use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+///
+/// use mem_barrier::{smp_rmb, smp_wmb};
+///
+/// let data = AtomicU32::new(0);
+/// let ready = AtomicBool::new(false);
+///
+/// // Writer
+/// data.store(42, Ordering::Relaxed);
+/// // Publish `data` before `ready`.
+/// smp_wmb();
+/// ready.store(true, Ordering::Relaxed);
+///
+/// // Reader
+/// if ready.load(Ordering::Relaxed) {
+///     // Don't read `data` before `ready`.
+///     smp_rmb();
+///     assert_eq!(data.load(Ordering::Relaxed), 42);
+/// }
+/// ```
+#[inline(always)]
+pub fn smp_wmb() {
+    mem_barrier(BarrierKind::Smp, BarrierType::Write);
+}
+
+/// A general DMA memory barrier.
+///
+/// This is the same as `mem_barrier(BarrierKind::Dma, BarrierType::General)` and corresponds to the `dma_mb` Linux function.
+#[inline(always)]
+pub fn dma_mb() {
+    mem_barrier(BarrierKind::Dma, BarrierType::General);
+}
+
+/// A read DMA memory barrier.
+///
+/// This is the same as `mem_barrier(BarrierKind::Dma, BarrierType::Read)` and corresponds to the `dma_rmb` Linux function.
+///
+/// See [`BarrierKind::Dma`] for an example.
+#[inline(always)]
+pub fn dma_rmb() {
+    mem_barrier(BarrierKind::Dma, BarrierType::Read);
+}
+
+/// A write DMA memory barrier.
+///
+/// This is the same as `mem_barrier(BarrierKind::Dma, BarrierType::Write)` and corresponds to the `dma_wmb` Linux function.
+///
+/// See [`BarrierKind::Dma`] for an example.
+#[inline(always)]
+pub fn dma_wmb() {
+    mem_barrier(BarrierKind::Dma, BarrierType::Write);
+}
+
+/// A DMA memory barrier before handing a buffer to a device.
+///
+/// Placed between writing a DMA buffer and handing it to the device, it makes the writes visible to the device before the device owns the buffer.
+///
+/// This is the same as [`dma_wmb`].
+///
+/// # Examples
+///
+/// ```
+/// use core::sync::atomic::{AtomicBool, Ordering};
+///
+/// use mem_barrier::{fence_after_dma_from_device, fence_before_dma_to_device};
+///
+/// let mut buffer = [0_u8; 4];
+/// let device_owns_buffer = AtomicBool::new(false);
+///
+/// // Fill the buffer and hand it to the device.
+/// buffer.fill(42);
+/// fence_before_dma_to_device();
+/// device_owns_buffer.store(true, Ordering::Relaxed);
+///
+/// // The device processes the buffer and hands it back.
+/// # device_owns_buffer.store(false, Ordering::Relaxed);
+///
+/// // Wait for the device, then read the buffer.
+/// if !device_owns_buffer.load(Ordering::Relaxed) {
+///     fence_after_dma_from_device();
+///     assert_eq!(buffer, [42; 4]);
+/// }
+/// ```
+#[inline(always)]
+pub fn fence_before_dma_to_device() {
+    dma_wmb();
+}
+
+/// A DMA memory barrier after taking a buffer back from a device.
+///
+/// Placed between observing that the device has finished with a DMA buffer and reading it, it prevents the reads from observing the buffer before the device has finished.
+///
+/// This is the same as [`dma_rmb`].
+///
+/// See [`fence_before_dma_to_device`] for an example.
+#[inline(always)]
+pub fn fence_after_dma_from_device() {
+    dma_rmb();
+}
+
+/// An acquire SMP memory barrier.
+///
+/// This is the same as `mem_barrier(BarrierKind::Smp, BarrierType::Acquire)`.
+/// Placed after a load, it prevents later loads and stores from being reordered before that load.
+///
+/// See [`release`] for an example.
+#[inline(always)]
+pub fn acquire() {
+    mem_barrier(BarrierKind::Smp, BarrierType::Acquire);
+}
+
+/// A release SMP memory barrier.
+///
+/// This is the same as `mem_barrier(BarrierKind::Smp, BarrierType::Release)`.
+/// Placed before a store, it prevents earlier loads and stores from being reordered after that store.
+///
+/// # Examples
+///
+/// ```
+/// use core::sync::atomic::{AtomicU32, Ordering};
+///
+/// use mem_barrier::{acquire, release};
+///
+/// let data = AtomicU32::new(0);
+/// let seq = AtomicU32::new(0);
+///
+/// // Writer
+/// data.store(42, Ordering::Relaxed);
+/// // Publish `data` before the new sequence number.
+/// release();
+/// seq.store(2, Ordering::Relaxed);
+///
+/// // Reader
+/// let s = seq.load(Ordering::Relaxed);
+/// // Don't read `data` before the sequence number.
+/// acquire();
+/// if s == 2 {
+///     assert_eq!(data.load(Ordering::Relaxed), 42);
+/// }
+/// ```
+#[inline(always)]
+pub fn release() {
+    mem_barrier(BarrierKind::Smp, BarrierType::Release);
+}
+
+/// A barrier type at the type level.
+///
+/// This is the [`BarrierType`] counterpart of [`Kind`].
+/// It is implemented by [`General`], [`Read`], [`Write`], [`Acquire`], and [`Release`].
+pub trait Type {
+    /// The corresponding barrier type.
+    const TYPE: BarrierType;
+}
+
+macro_rules! type_markers {
+    ($($name:ident,)*) => {
+        $(
+            #[doc = concat!("The [`", stringify!($name), "`](BarrierType::", stringify!($name), ") barrier type at the type level.")]
+            #[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+            pub struct $name;
+
+            impl Type for $name {
+                const TYPE: BarrierType = BarrierType::$name;
+            }
+        )*
+    };
+}
+
+type_markers! {
+    General,
+    Read,
+    Write,
+    Acquire,
+    Release,
+}
+
+/// A scope guard that runs a memory barrier when created and another one when dropped.
+///
+/// The guard runs barriers of kind `K`.
+/// When dropped, it runs a barrier of type `E`, which defaults to [`Acquire`].
+/// [`new`](Self::new) runs a [`Release`](BarrierType::Release) barrier when created.
+/// This orders earlier memory accesses before writes in the scope and reads in the scope before later memory accesses.
+///
+/// Since the kind and the type of the closing barrier are type parameters, the guard is zero-sized.
+///
+/// # Examples
+///
+/// ```
+/// use mem_barrier::{BarrierScope, BarrierType, Mmio, Read, Smp};
+///
+/// {
+///     let _scope = BarrierScope::<Smp>::new();
+///     // ...
+/// }
+/// {
+///     let _scope = BarrierScope::<Mmio, Read>::with_enter(BarrierType::Write);
+///     // ...
+/// }
+/// ```
+#[must_use = "the closing barrier runs when the guard is dropped"]
+#[derive(Debug)]
+pub struct BarrierScope<K: Kind, E: Type = Acquire> {
+    _marker: PhantomData<(K, E)>,
+}
+
+impl<K: Kind> BarrierScope<K> {
+    /// Runs a release barrier and returns a guard that runs an acquire barrier when dropped.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::with_enter(BarrierType::Release)
+    }
+}
+
+impl<K: Kind> Default for BarrierScope<K> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Kind, E: Type> BarrierScope<K, E> {
+    /// Runs an `enter` barrier and returns a guard that runs an `E` barrier when dropped.
+    #[inline(always)]
+    pub fn with_enter(enter: BarrierType) -> Self {
+        K::barrier(enter);
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K: Kind, E: Type> Drop for BarrierScope<K, E> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        K::barrier(E::TYPE);
+    }
+}
+
+/// Runs a closure between two memory barriers.
+///
+/// This runs `mem_barrier(kind, ty)`, calls `f`, runs `mem_barrier(kind, ty)` again, and returns the result of `f`.
+/// Memory accesses in `f` are thus ordered according to `kind` and `ty` with respect to memory accesses before and after this call.
+///
+/// # Examples
+///
+/// ```
+/// use mem_barrier::{BarrierKind, BarrierType, with_barrier};
+///
+/// let mut reg = 0_u32;
+///
+/// let value = with_barrier(BarrierKind::Mmio, BarrierType::General, || {
+///     // SAFETY: The pointer is valid for reads and writes.
+///     unsafe {
+///         core::ptr::write_volatile(&mut reg, 1);
+///         core::ptr::read_volatile(&reg)
+///     }
+/// });
+/// # assert_eq!(value, 1);
+/// ```
+#[inline]
+pub fn with_barrier<T, F: FnOnce() -> T>(kind: BarrierKind, ty: BarrierType, f: F) -> T {
+    mem_barrier(kind, ty);
+    let ret = f();
+    mem_barrier(kind, ty);
+    ret
+}
+
+/// A spin loop hint.
+///
+/// This function signals the CPU that the caller is busy-waiting, for example, in a spin loop polling a memory location.
+/// The CPU may use this hint to save power or to yield resources to other hardware threads.
+///
+/// This is not a memory barrier and does not order any memory accesses.
+///
+/// # Current implementation
+///
+/// On AArch64, this runs an [ISB] instruction.
+/// Most cores execute `YIELD` as a no-op, while `ISB` reliably delays the loop for a short time.
+/// This is the same choice as in [`core::hint::spin_loop`].
+/// On ARMv7 and M-profile cores, this runs a [YIELD] instruction.
+/// On RISC-V with the Zihintpause extension, this runs a [PAUSE] instruction.
+/// On x86, this runs a [PAUSE][x86 PAUSE] instruction.
+/// On other architectures, this is a no-op.
+///
+/// [ISB]: https://developer.arm.com/documentation/ddi0602/2025-09/Base-Instructions/ISB--Instruction-synchronization-barrier-
+/// [YIELD]: https://developer.arm.com/documentation/ddi0597/2025-09/Base-Instructions/YIELD--Yield-hint-
+/// [PAUSE]: https://docs.riscv.org/reference/isa/unpriv/zihintpause.html
+/// [x86 PAUSE]: https://www.felixcloutier.com/x86/pause
+#[inline(always)]
+pub fn spin_loop_hint() {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "loom")] {
+            // This lets loom schedule other threads while spinning.
+            loom::hint::spin_loop();
+        } else if #[cfg(miri)] {
+            // Miri cannot run inline assembly.
+            core::hint::spin_loop();
+        } else if #[cfg(target_arch = "aarch64")] {
+            // SAFETY: This is just an instruction synchronization barrier.
+            unsafe {
+                core::arch::asm!("isb sy", options(nomem, preserves_flags, nostack));
+            }
+        } else if #[cfg(any(
+            mem_barrier_arm = "v6m",
+            mem_barrier_arm = "v7m",
+            mem_barrier_arm = "v7"
+        ))] {
+            // SAFETY: This is just a hint.
+            unsafe {
+                core::arch::asm!("yield", options(nomem, preserves_flags, nostack));
+            }
+        } else if #[cfg(all(
+            any(target_arch = "riscv32", target_arch = "riscv64"),
+            target_feature = "zihintpause"
+        ))] {
+            // SAFETY: This is just a hint.
+            unsafe {
+                core::arch::asm!("pause", options(nomem, preserves_flags, nostack));
+            }
+        } else if #[cfg(any(target_arch = "x86", target_arch = "x86_64"))] {
+            // SAFETY: This is just a hint.
+            unsafe {
+                core::arch::asm!("pause", options(nomem, preserves_flags, nostack));
+            }
+        } else if #[cfg(mem_barrier_asm)] {
+            // SAFETY: This is empty.
+            unsafe {
+                core::arch::asm!("", options(nomem, preserves_flags, nostack));
+            }
+        }
+    }
+}
+
+/// A compiler barrier.
+///
+/// This function does not run any CPU instructions.
+/// Instead, it only prevents the compiler from moving memory accesses through the barrier.
+///
+/// This is the same as `mem_barrier(BarrierKind::Compiler, BarrierType::General)` and corresponds to the `barrier` Linux function.
+/// It runs [`compiler_fence`]`(`[`Ordering::SeqCst`]`)`.
+/// On BPF, C-SKY, Hexagon, NVPTX, and SPARC, where LLVM does not compile compiler fences to nothing, it runs empty inline assembly instead, which requires the `nightly` feature.
+/// Without it, this runs a full memory barrier, except on BPF, which requires the `nightly` feature.
+///
+/// [`compiler_fence`]: core::sync::atomic::compiler_fence
+/// [`Ordering::SeqCst`]: core::sync::atomic::Ordering::SeqCst
+///
+/// # Examples
+///
+/// ```
+/// use mem_barrier::compiler_barrier;
+///
+/// let mut a = 0_u32;
+/// let mut b = 0_u32;
+///
+/// // SAFETY: The pointers are valid for writes.
+/// unsafe {
+///     core::ptr::write_volatile(&mut a, 1);
+///     // Don't move the following write before the previous one.
+///     compiler_barrier();
+///     core::ptr::write_volatile(&mut b, 2);
+/// }
+/// # assert_eq!((a, b), (1, 2));
+/// ```
+#[doc(alias = "barrier")]
+#[inline(always)]
+pub fn compiler_barrier() {
+    arch::compiler_barrier();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
     fn test_memory_barrier() {
+        for &kind in BarrierKind::all() {
+            for &ty in BarrierType::all() {
+                mem_barrier(kind, ty);
+                mem_barrier_asm(kind, ty);
+            }
+        }
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn test_memory_barrier_const() {
+        mem_barrier_const::<{ BarrierKind::Mmio }, { BarrierType::General }>();
+        mem_barrier_const::<{ BarrierKind::Smp }, { BarrierType::Read }>();
+        mem_barrier_const::<{ BarrierKind::Dma }, { BarrierType::Write }>();
+    }
+
+    #[test]
+    fn test_memory_barrier_macro() {
+        mem_barrier!();
+        mem_barrier!(Smp);
+        mem_barrier!(Dma, Write);
+    }
+
+    #[test]
+    fn test_kind_markers() {
+        fn barriers<K: Kind>() {
+            for &ty in BarrierType::all() {
+                K::barrier(ty);
+            }
+        }
+
+        barriers::<Mmio>();
+        barriers::<Smp>();
+        barriers::<Dma>();
+        assert_eq!(Smp::KIND, BarrierKind::Smp);
+    }
+
+    #[test]
+    fn test_compiler_barrier() {
+        let mut a = 0_u32;
+        let mut b = 0_u32;
+
+        // SAFETY: The pointers are valid for writes.
+        unsafe {
+            core::ptr::write_volatile(&mut a, 1);
+            compiler_barrier();
+            core::ptr::write_volatile(&mut b, 2);
+        }
+
+        // SAFETY: The pointers are valid for reads.
+        unsafe {
+            assert_eq!(core::ptr::read_volatile(&a), 1);
+            assert_eq!(core::ptr::read_volatile(&b), 2);
+        }
+    }
+
+    #[test]
+    fn test_typed_barriers() {
+        for &kind in BarrierKind::all() {
+            full_barrier(kind);
+            read_barrier(kind);
+            write_barrier(kind);
+        }
+    }
+
+    #[test]
+    fn test_linux_barriers() {
+        mb();
+        rmb();
+        wmb();
+        smp_mb();
+        smp_rmb();
+        smp_wmb();
+        dma_mb();
+        dma_rmb();
+        dma_wmb();
+    }
+
+    #[test]
+    fn test_barrier_type_from_ordering() {
+        assert_eq!(BarrierType::from(Ordering::Relaxed), BarrierType::General);
+        assert_eq!(BarrierType::from(Ordering::Acquire), BarrierType::Acquire);
+        assert_eq!(BarrierType::from(Ordering::Release), BarrierType::Release);
+        assert_eq!(BarrierType::from(Ordering::AcqRel), BarrierType::General);
+        assert_eq!(BarrierType::from(Ordering::SeqCst), BarrierType::General);
+
+        mem_barrier_for(BarrierKind::Smp, Ordering::Acquire);
+    }
+
+    #[test]
+    fn test_access_ordering() {
+        for &ty in BarrierType::all() {
+            assert_eq!(ty.ordering().barrier_type(), ty);
+            assert_eq!(AccessOrdering::from(ty), ty.ordering());
+        }
+
+        let cases = [
+            (AccessSet::READ, AccessSet::WRITE, BarrierType::Acquire),
+            (AccessSet::all(), AccessSet::READ, BarrierType::General),
+            (AccessSet::WRITE, AccessSet::READ, BarrierType::General),
+            (AccessSet::WRITE, AccessSet::all(), BarrierType::General),
+            (AccessSet::READ, AccessSet::empty(), BarrierType::General),
+        ];
+        for (before, after, ty) in cases {
+            let ordering = AccessOrdering::new(before, after);
+            assert_eq!(ordering.barrier_type(), ty, "{ordering:?}");
+        }
+
+        assert_eq!(AccessSet::READ | AccessSet::WRITE, AccessSet::all());
+    }
+
+    #[test]
+    fn test_mem_barrier_ordered() {
+        let sets = [
+            AccessSet::empty(),
+            AccessSet::READ,
+            AccessSet::WRITE,
+            AccessSet::all(),
+        ];
+        for &kind in BarrierKind::all() {
+            for before in sets {
+                for after in sets {
+                    mem_barrier_ordered(kind, AccessOrdering::new(before, after));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_barrier_kind_strength() {
+        assert!(BarrierKind::Compiler < BarrierKind::NonShareable);
+        assert!(BarrierKind::NonShareable < BarrierKind::Smp);
+        assert!(BarrierKind::Smp < BarrierKind::Virt);
+        assert!(BarrierKind::Virt < BarrierKind::Dma);
+        assert!(BarrierKind::Dma < BarrierKind::DmaCompletion);
+        assert!(BarrierKind::DmaCompletion < BarrierKind::Mmio);
+        assert!(BarrierKind::Dma < BarrierKind::System);
+        assert!(BarrierKind::System < BarrierKind::Mmio);
+        assert_eq!(
+            BarrierKind::System.partial_cmp(&BarrierKind::DmaCompletion),
+            None
+        );
+        assert!(BarrierKind::Device < BarrierKind::Mmio);
+        assert!(BarrierKind::Compiler < BarrierKind::Instruction);
+        assert_eq!(BarrierKind::Device.partial_cmp(&BarrierKind::Smp), None);
+        assert_eq!(
+            BarrierKind::Instruction.partial_cmp(&BarrierKind::Mmio),
+            None
+        );
+
+        assert_eq!(
+            BarrierKind::Smp.combine(BarrierKind::Compiler),
+            Some(BarrierKind::Smp)
+        );
+        assert_eq!(
+            BarrierKind::Device.combine(BarrierKind::Smp),
+            Some(BarrierKind::Mmio)
+        );
+        assert_eq!(
+            BarrierKind::Instruction.combine(BarrierKind::Compiler),
+            Some(BarrierKind::Instruction)
+        );
+        assert_eq!(
+            BarrierKind::System.combine(BarrierKind::DmaCompletion),
+            Some(BarrierKind::Mmio)
+        );
+        assert_eq!(BarrierKind::Instruction.combine(BarrierKind::Mmio), None);
+    }
+
+    #[test]
+    fn test_display_from_str() {
+        extern crate std;
+        use std::string::ToString;
+
+        for &kind in BarrierKind::all() {
+            assert_eq!(kind.to_string().parse(), Ok(kind));
+        }
+        for &ty in BarrierType::all() {
+            assert_eq!(ty.to_string().parse(), Ok(ty));
+        }
+        assert_eq!("Smp".parse::<BarrierKind>(), Err(ParseBarrierError(())));
+        assert_eq!("".parse::<BarrierType>(), Err(ParseBarrierError(())));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde_test::{Token, assert_tokens};
+
+        assert_tokens(
+            &BarrierKind::Smp,
+            &[Token::UnitVariant {
+                name: "BarrierKind",
+                variant: "smp",
+            }],
+        );
+        assert_tokens(
+            &BarrierKind::DmaCompletion,
+            &[Token::UnitVariant {
+                name: "BarrierKind",
+                variant: "dma_completion",
+            }],
+        );
+        assert_tokens(
+            &BarrierType::Acquire,
+            &[Token::UnitVariant {
+                name: "BarrierType",
+                variant: "acquire",
+            }],
+        );
+    }
+
+    #[test]
+    fn test_try_from_u8() {
+        for &kind in BarrierKind::all() {
+            assert_eq!(BarrierKind::try_from(kind as u8), Ok(kind));
+        }
+        for &ty in BarrierType::all() {
+            assert_eq!(BarrierType::try_from(ty as u8), Ok(ty));
+        }
+        assert_eq!(
+            BarrierKind::try_from(BarrierKind::all().len() as u8),
+            Err(TryFromBarrierError(()))
+        );
+        assert_eq!(
+            BarrierType::try_from(BarrierType::all().len() as u8),
+            Err(TryFromBarrierError(()))
+        );
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_mem_barrier_ffi() {
+        mem_barrier_ffi(BarrierKind::Smp as u8, BarrierType::Read as u8);
+        mem_barrier_ffi(u8::MAX, u8::MAX);
+    }
+
+    #[cfg(feature = "trace")]
+    extern crate std;
+
+    /// Serializes the tests that set the trace hook, since it is global.
+    #[cfg(feature = "trace")]
+    static TRACE_HOOK_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn test_trace_hook() {
+        use core::sync::atomic::AtomicBool;
+
+        static CALLED: AtomicBool = AtomicBool::new(false);
+
+        fn hook(kind: BarrierKind, ty: BarrierType) {
+            if (kind, ty) == (BarrierKind::Dma, BarrierType::Release) {
+                CALLED.store(true, Ordering::Relaxed);
+            }
+        }
+
+        let _lock = TRACE_HOOK_LOCK.lock().unwrap();
+        set_trace_hook(Some(hook));
+        mem_barrier(BarrierKind::Dma, BarrierType::Release);
+        set_trace_hook(None);
+        assert!(CALLED.load(Ordering::Relaxed));
+    }
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn test_trace_hook_kind() {
+        use core::cell::Cell;
+
+        // Other tests run barriers concurrently, so we only count the barriers of this thread.
+        std::thread_local! {
+            static COUNT: Cell<usize> = const { Cell::new(0) };
+        }
+
+        fn hook(kind: BarrierKind, ty: BarrierType) {
+            if (kind, ty) == (BarrierKind::Smp, BarrierType::Write) {
+                COUNT.set(COUNT.get() + 1);
+            }
+        }
+
+        let _lock = TRACE_HOOK_LOCK.lock().unwrap();
+        set_trace_hook(Some(hook));
+        Smp::barrier(BarrierType::Write);
+        {
+            let _scope = BarrierScope::<Smp, Write>::with_enter(BarrierType::Write);
+        }
+        set_trace_hook(None);
+        assert_eq!(COUNT.get(), 3);
+    }
+
+    #[cfg(feature = "loom")]
+    #[test]
+    fn test_loom_dma_ring() {
+        use loom::sync::Arc;
+        use loom::sync::atomic::AtomicUsize;
+
+        loom::model(|| {
+            let desc = Arc::new(AtomicUsize::new(0));
+            let head = Arc::new(AtomicUsize::new(0));
+
+            let producer = {
+                let desc = desc.clone();
+                let head = head.clone();
+                loom::thread::spawn(move || {
+                    desc.store(42, Ordering::Relaxed);
+                    dma_wmb();
+                    head.store(1, Ordering::Relaxed);
+                })
+            };
+
+            if head.load(Ordering::Relaxed) == 1 {
+                dma_rmb();
+                assert_eq!(desc.load(Ordering::Relaxed), 42);
+            }
+
+            producer.join().unwrap();
+        });
+    }
+
+    #[test]
+    fn test_implementation_consts() {
+        const { assert!(!USES_NIGHTLY_INTRINSICS || USES_STDARCH) };
+
+        #[cfg(target_arch = "x86_64")]
+        {
+            assert_eq!(ARCH, "x86_64");
+            assert_eq!(USES_STDARCH, cfg!(all(feature = "stdarch", not(miri))));
+            const { assert!(!USES_NIGHTLY_INTRINSICS) };
+        }
+    }
+
+    #[test]
+    fn test_barrier_mnemonic() {
+        const COMPILER: &str = barrier_mnemonic(BarrierKind::Compiler, BarrierType::General);
+        assert_eq!(COMPILER, "");
+
+        #[cfg(target_arch = "aarch64")]
+        {
+            assert_eq!(
+                barrier_mnemonic(BarrierKind::Mmio, BarrierType::General),
+                "dsb sy"
+            );
+            assert_eq!(
+                barrier_mnemonic(BarrierKind::Dma, BarrierType::Read),
+                "dmb oshld"
+            );
+            assert_eq!(
+                barrier_mnemonic(BarrierKind::Device, BarrierType::General),
+                "dmb osh"
+            );
+            assert_eq!(
+                barrier_mnemonic(BarrierKind::Device, BarrierType::Read),
+                "dmb oshld"
+            );
+            assert_eq!(
+                barrier_mnemonic(BarrierKind::Device, BarrierType::Write),
+                "dmb oshst"
+            );
+            assert_eq!(
+                barrier_mnemonic(BarrierKind::Instruction, BarrierType::General),
+                "isb"
+            );
+
+            // Store-only barriers do not order earlier loads, so release barriers use the full barrier.
+            let cases = [
+                (BarrierKind::Mmio, BarrierType::Acquire, "dsb ld"),
+                (BarrierKind::Mmio, BarrierType::Release, "dsb sy"),
+                (BarrierKind::Dma, BarrierType::Acquire, "dmb oshld"),
+                (BarrierKind::Dma, BarrierType::Release, "dmb osh"),
+                (BarrierKind::System, BarrierType::General, "dmb sy"),
+                (BarrierKind::System, BarrierType::Acquire, "dmb ld"),
+                (BarrierKind::System, BarrierType::Write, "dmb st"),
+                (BarrierKind::System, BarrierType::Release, "dmb sy"),
+                #[cfg(not(feature = "single_core"))]
+                (BarrierKind::Smp, BarrierType::Acquire, "dmb ishld"),
+                #[cfg(not(feature = "single_core"))]
+                (BarrierKind::Smp, BarrierType::Release, "dmb ish"),
+            ];
+            for (kind, ty, mnemonic) in cases {
+                assert_eq!(barrier_mnemonic(kind, ty), mnemonic, "{kind:?} {ty:?}");
+            }
+        }
+
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        {
+            let cases = [
+                (BarrierKind::Mmio, BarrierType::General, "fence iorw, iorw"),
+                (BarrierKind::Mmio, BarrierType::Read, "fence ir, ir"),
+                (BarrierKind::Mmio, BarrierType::Write, "fence ow, ow"),
+                (BarrierKind::Mmio, BarrierType::Acquire, "fence ir, iorw"),
+                (BarrierKind::Mmio, BarrierType::Release, "fence iorw, ow"),
+                (BarrierKind::Dma, BarrierType::General, "fence rw, rw"),
+                (BarrierKind::Dma, BarrierType::Read, "fence r, r"),
+                (BarrierKind::Dma, BarrierType::Write, "fence w, w"),
+                (BarrierKind::Dma, BarrierType::Acquire, "fence r, rw"),
+                (BarrierKind::Dma, BarrierType::Release, "fence rw, w"),
+                #[cfg(not(any(feature = "single_core", target_feature = "ztso")))]
+                (BarrierKind::Smp, BarrierType::Acquire, "fence r, rw"),
+                #[cfg(not(any(feature = "single_core", target_feature = "ztso")))]
+                (BarrierKind::Smp, BarrierType::Release, "fence rw, w"),
+                (BarrierKind::Device, BarrierType::Write, "fence o, o"),
+            ];
+            for (kind, ty, mnemonic) in cases {
+                assert_eq!(barrier_mnemonic(kind, ty), mnemonic, "{kind:?} {ty:?}");
+            }
+        }
+
+        #[cfg(any(target_arch = "powerpc", target_arch = "powerpc64"))]
+        {
+            // Instruction barriers are separate from the data barriers.
+            let cases = [
+                (BarrierKind::Mmio, BarrierType::General, "sync"),
+                (BarrierKind::Mmio, BarrierType::Write, "sync"),
+                (BarrierKind::Dma, BarrierType::Write, "eieio"),
+                (BarrierKind::Instruction, BarrierType::General, "isync"),
+            ];
+            for (kind, ty, mnemonic) in cases {
+                assert_eq!(barrier_mnemonic(kind, ty), mnemonic, "{kind:?} {ty:?}");
+            }
+        }
+
+        #[cfg(target_arch = "loongarch64")]
+        {
+            // SMP barriers use ordering hints, while other kinds of barriers use completion hints.
+            let cases = [
+                (BarrierKind::Mmio, BarrierType::General, "dbar 0"),
+                (BarrierKind::Mmio, BarrierType::Read, "dbar 0x5"),
+                (BarrierKind::Mmio, BarrierType::Write, "dbar 0xa"),
+                (BarrierKind::Dma, BarrierType::Acquire, "dbar 0x4"),
+                (BarrierKind::Dma, BarrierType::Release, "dbar 0x2"),
+                (BarrierKind::Virt, BarrierType::General, "dbar 0x10"),
+                (BarrierKind::Virt, BarrierType::Read, "dbar 0x15"),
+                (BarrierKind::Virt, BarrierType::Write, "dbar 0x1a"),
+                (BarrierKind::Virt, BarrierType::Acquire, "dbar 0x14"),
+                (BarrierKind::Virt, BarrierType::Release, "dbar 0x12"),
+                (BarrierKind::Instruction, BarrierType::General, "ibar 0"),
+            ];
+            for (kind, ty, mnemonic) in cases {
+                assert_eq!(barrier_mnemonic(kind, ty), mnemonic, "{kind:?} {ty:?}");
+            }
+        }
+
+        #[cfg(all(
+            any(target_arch = "riscv32", target_arch = "riscv64"),
+            target_feature = "ztso"
+        ))]
+        {
+            // Virt barriers are SMP barriers that do not depend on the `single_core` feature.
+            assert_eq!(
+                barrier_mnemonic(BarrierKind::Virt, BarrierType::Acquire),
+                ""
+            );
+            assert_eq!(barrier_mnemonic(BarrierKind::Virt, BarrierType::Write), "");
+            assert_eq!(
+                barrier_mnemonic(BarrierKind::Virt, BarrierType::General),
+                "fence rw, rw"
+            );
+            assert_eq!(
+                barrier_mnemonic(BarrierKind::Dma, BarrierType::Write),
+                "fence w, w"
+            );
+        }
+
+        #[cfg(target_arch = "x86_64")]
+        {
+            assert_eq!(
+                barrier_mnemonic(BarrierKind::Mmio, BarrierType::General),
+                "mfence"
+            );
+            assert_eq!(
+                barrier_mnemonic(BarrierKind::Dma, BarrierType::Write),
+                "sfence"
+            );
+            assert_eq!(barrier_mnemonic(BarrierKind::Smp, BarrierType::Read), "");
+            assert_eq!(
+                barrier_mnemonic(BarrierKind::Instruction, BarrierType::General),
+                "cpuid"
+            );
+
+            // MMIO barriers never use locked instructions, since those do not order write-combining stores.
+            let cases = [
+                (BarrierKind::Mmio, BarrierType::Read, "lfence"),
+                (BarrierKind::Mmio, BarrierType::Write, "sfence"),
+                (BarrierKind::Mmio, BarrierType::Acquire, "mfence"),
+                (BarrierKind::Mmio, BarrierType::Release, "mfence"),
+                (BarrierKind::Dma, BarrierType::General, "mfence"),
+                #[cfg(all(feature = "x86-lock-fence", not(feature = "single_core")))]
+                (
+                    BarrierKind::Smp,
+                    BarrierType::General,
+                    "lock add dword ptr [rsp], 0",
+                ),
+            ];
+            for (kind, ty, mnemonic) in cases {
+                assert_eq!(barrier_mnemonic(kind, ty), mnemonic, "{kind:?} {ty:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_noop() {
+        const { assert!(is_noop(BarrierKind::Compiler, BarrierType::General)) };
+        assert!(!is_noop(BarrierKind::Mmio, BarrierType::General));
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            assert!(is_noop(BarrierKind::Smp, BarrierType::Read));
+            assert!(is_noop(BarrierKind::Smp, BarrierType::Write));
+            assert!(!is_noop(BarrierKind::Dma, BarrierType::General));
+        }
+    }
+
+    #[test]
+    fn test_distinguishes() {
+        const { assert!(distinguishes(BarrierKind::Compiler, BarrierType::Read)) };
+        for &kind in BarrierKind::all() {
+            assert!(distinguishes(kind, BarrierType::General), "{kind:?}");
+        }
+        assert!(!distinguishes(
+            BarrierKind::Instruction,
+            BarrierType::Acquire
+        ));
+        assert!(str_eq("dmb ish", "dmb ish"));
+        assert!(!str_eq("dmb ish", "dmb ishld"));
+
+        #[cfg(all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            not(feature = "single_core")
+        ))]
+        assert!(distinguishes(BarrierKind::Smp, BarrierType::Read));
+
+        #[cfg(all(target_arch = "aarch64", not(feature = "single_core")))]
+        {
+            assert!(distinguishes(BarrierKind::Smp, BarrierType::Acquire));
+            assert!(!distinguishes(BarrierKind::Smp, BarrierType::Release));
+        }
+    }
+
+    #[cfg(feature = "single_core")]
+    #[test]
+    fn test_single_core() {
+        for &ty in BarrierType::all() {
+            assert!(is_noop(BarrierKind::Smp, ty));
+        }
+        assert!(!is_noop(BarrierKind::Virt, BarrierType::General));
+        assert!(!is_noop(BarrierKind::Mmio, BarrierType::General));
+        assert!(!is_noop(BarrierKind::Dma, BarrierType::General));
+    }
+
+    #[test]
+    fn test_acquire_release() {
+        acquire();
+        release();
+    }
+
+    #[test]
+    fn test_barrier_builder() {
+        assert_eq!(
+            Barrier::new(),
+            Barrier::new()
+                .kind(BarrierKind::default())
+                .ty(BarrierType::default())
+        );
+        assert_eq!(Barrier::new(), Barrier::default());
+
+        let barrier = Barrier::new().kind(BarrierKind::Dma).ty(BarrierType::Write);
+        barrier.execute();
+        assert_eq!(
+            barrier.mnemonic(),
+            barrier_mnemonic(BarrierKind::Dma, BarrierType::Write)
+        );
+        assert!(Barrier::new().kind(BarrierKind::Compiler).is_noop());
+
+        assert_eq!(Barrier::MMIO_GENERAL, Barrier::new());
+        assert_eq!(
+            Barrier::DMA_COMPLETION_ACQUIRE,
+            Barrier::new()
+                .kind(BarrierKind::DmaCompletion)
+                .ty(BarrierType::Acquire)
+        );
+        Barrier::SMP_READ.execute();
+
+        assert_eq!(
+            Barrier::for_ordering(BarrierKind::Dma, Ordering::Release),
+            Barrier::DMA_RELEASE
+        );
+        assert_eq!(
+            Barrier::for_ordering(BarrierKind::Mmio, Ordering::SeqCst),
+            Barrier::MMIO_GENERAL
+        );
+        assert_eq!(
+            Barrier::for_ordering(BarrierKind::Mmio, Ordering::Relaxed),
+            Barrier::new().kind(BarrierKind::Compiler)
+        );
+    }
+
+    #[test]
+    fn test_barrier_scope() {
+        {
+            let _scope = BarrierScope::<Smp>::new();
+        }
+        {
+            let _scope = BarrierScope::<Mmio, Read>::with_enter(BarrierType::Write);
+        }
+    }
+
+    const _: () = assert!(core::mem::size_of::<BarrierScope<Smp>>() == 0);
+    const _: () = assert!(core::mem::size_of::<BarrierScope<Dma, General>>() == 0);
+
+    #[test]
+    fn test_with_barrier() {
+        assert_eq!(
+            with_barrier(BarrierKind::Smp, BarrierType::General, || 42),
+            42
+        );
+    }
+
+    #[test]
+    fn test_full_system_barrier() {
+        full_system_barrier();
+
+        for &kind in BarrierKind::all() {
+            if kind != BarrierKind::Instruction {
+                assert!(BarrierKind::Mmio.implies(kind), "{kind:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_spin_loop_hint() {
+        spin_loop_hint();
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    #[test]
+    fn test_aarch64_barriers() {
+        aarch64::isb();
+        aarch64::speculation_barrier();
+        aarch64::csdb();
+
+        for domain in [
+            aarch64::Domain::NonShareable,
+            aarch64::Domain::InnerShareable,
+            aarch64::Domain::OuterShareable,
+            aarch64::Domain::FullSystem,
+        ] {
+            for &ty in BarrierType::all() {
+                aarch64::dsb(domain, ty);
+            }
+        }
+
+        aarch64::send_event();
+        aarch64::wait_for_event();
+    }
+
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    #[test]
+    fn test_riscv_barriers() {
+        use riscv::FenceSet;
+
+        riscv::fence(FenceSet::O | FenceSet::W, FenceSet::I | FenceSet::R);
+        riscv::fence(FenceSet::empty(), FenceSet::R);
+        riscv::fence_tso();
+        riscv::fence_i();
+    }
+
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    #[test]
+    fn test_riscv_common_fences() {
+        use riscv::{FenceSet, fence};
+
+        const I: FenceSet = FenceSet::I;
+        const O: FenceSet = FenceSet::O;
+        const R: FenceSet = FenceSet::R;
+        const W: FenceSet = FenceSet::W;
+
+        // The fences documented for `riscv::fence` that `mem_barrier` runs.
+        let barriers = [
+            (
+                BarrierKind::Mmio,
+                BarrierType::General,
+                I | O | R | W,
+                I | O | R | W,
+                "fence iorw, iorw",
+            ),
+            (
+                BarrierKind::Mmio,
+                BarrierType::Read,
+                I | R,
+                I | R,
+                "fence ir, ir",
+            ),
+            (
+                BarrierKind::Mmio,
+                BarrierType::Write,
+                O | W,
+                O | W,
+                "fence ow, ow",
+            ),
+            (
+                BarrierKind::Mmio,
+                BarrierType::Acquire,
+                I | R,
+                I | O | R | W,
+                "fence ir, iorw",
+            ),
+            (
+                BarrierKind::Mmio,
+                BarrierType::Release,
+                I | O | R | W,
+                O | W,
+                "fence iorw, ow",
+            ),
+            (
+                BarrierKind::Dma,
+                BarrierType::General,
+                R | W,
+                R | W,
+                "fence rw, rw",
+            ),
+            (BarrierKind::Dma, BarrierType::Read, R, R, "fence r, r"),
+            (BarrierKind::Dma, BarrierType::Write, W, W, "fence w, w"),
+            (
+                BarrierKind::Dma,
+                BarrierType::Acquire,
+                R,
+                R | W,
+                "fence r, rw",
+            ),
+            (
+                BarrierKind::Dma,
+                BarrierType::Release,
+                R | W,
+                W,
+                "fence rw, w",
+            ),
+            (
+                BarrierKind::Device,
+                BarrierType::General,
+                I | O,
+                I | O,
+                "fence io, io",
+            ),
+            (BarrierKind::Device, BarrierType::Read, I, I, "fence i, i"),
+            (BarrierKind::Device, BarrierType::Write, O, O, "fence o, o"),
+            (
+                BarrierKind::Device,
+                BarrierType::Acquire,
+                I,
+                I | O,
+                "fence i, io",
+            ),
+            (
+                BarrierKind::Device,
+                BarrierType::Release,
+                I | O,
+                O,
+                "fence io, o",
+            ),
+        ];
+        for (kind, ty, pred, succ, mnemonic) in barriers {
+            fence(pred, succ);
+            assert_eq!(barrier_mnemonic(kind, ty), mnemonic);
+        }
+
+        // The fences documented for the Linux I/O accessors.
+        for (pred, succ) in [
+            (I, I | R),
+            (W, O),
+            (I | O, I),
+            (I, I | O | R),
+            (I | O | W, O),
+            (O, I | O),
+        ] {
+            fence(pred, succ);
+        }
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn test_x86_features() {
+        extern crate std;
+        use std::format;
+
+        use x86::Features;
+
+        let features = x86::features();
+        assert_eq!(x86::features(), features);
+        #[cfg(all(target_arch = "x86_64", not(miri)))]
+        assert!(features.contains(Features::SSE2));
+
+        for &kind in BarrierKind::all() {
+            for &ty in BarrierType::all() {
+                x86::mem_barrier_with(features, kind, ty);
+                x86::mem_barrier_with(Features::empty(), kind, ty);
+            }
+        }
+
+        assert_eq!(format!("{:?}", Features::empty()), "Features(empty)");
+        assert_eq!(
+            format!("{:?}", Features::SSE2 | Features::SERIALIZE),
+            "Features(sse2 | serialize)"
+        );
+    }
+
+    #[test]
+    fn test_fallback_memory_barrier() {
         for kind in [
-            BarrierKind::Mmio,
-            BarrierKind::Smp,
-            BarrierKind::Dma,
-            BarrierKind::Compiler,
+            arch::CpuBarrierKind::Mmio,
+            arch::CpuBarrierKind::Smp,
+            arch::CpuBarrierKind::Dma,
         ] {
-            for ty in [BarrierType::General, BarrierType::Read, BarrierType::Write] {
-                mem_barrier(kind, ty);
+            for &ty in BarrierType::all() {
+                arch::fallback::mem_barrier(kind, ty);
+                assert_eq!(arch::fallback::mnemonic(kind, ty), "fence");
             }
         }
+        arch::fallback::instruction_barrier();
+        assert_eq!(arch::fallback::instruction_barrier_mnemonic(), "fence");
+        const { assert!(!arch::fallback::USES_STDARCH && !arch::fallback::USES_NIGHTLY_INTRINSICS) };
     }
 }
@@ -0,0 +1,286 @@
+//! RISC-V-specific barriers.
+
+use core::fmt;
+use core::ops::{BitOr, BitOrAssign};
+
+/// A set of memory operations ordered by a [`fence`].
+///
+/// Sets can be combined with `|`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+/// # {
+/// use mem_barrier::riscv::{FenceSet, fence};
+///
+/// // Order device output and memory writes before device input and memory reads.
+/// fence(FenceSet::O | FenceSet::W, FenceSet::I | FenceSet::R);
+/// # }
+/// ```
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+pub struct FenceSet(u8);
+
+impl FenceSet {
+    /// Device input.
+    pub const I: Self = Self(0b1000);
+
+    /// Device output.
+    pub const O: Self = Self(0b0100);
+
+    /// Memory reads.
+    pub const R: Self = Self(0b0010);
+
+    /// Memory writes.
+    pub const W: Self = Self(0b0001);
+
+    /// Returns an empty set.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns the set of all memory operations.
+    #[inline]
+    pub const fn all() -> Self {
+        Self(0b1111)
+    }
+
+    /// Returns the raw bits of this set as encoded in the `fence` instruction.
+    #[inline]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns `true` if this set is empty.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns `true` if this set contains all operations of `other`.
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the union of both sets.
+    #[inline]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOr for FenceSet {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl BitOrAssign for FenceSet {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = self.union(rhs);
+    }
+}
+
+impl fmt::Debug for FenceSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FenceSet(")?;
+        if self.is_empty() {
+            f.write_str("empty")?;
+        }
+        for (set, c) in [
+            (Self::I, 'i'),
+            (Self::O, 'o'),
+            (Self::R, 'r'),
+            (Self::W, 'w'),
+        ] {
+            if self.contains(set) {
+                fmt::Write::write_char(f, c)?;
+            }
+        }
+        f.write_str(")")
+    }
+}
+
+/// A memory ordering fence.
+///
+/// This runs a `fence pred, succ` instruction.
+/// It orders all operations in the predecessor set `pred` before all operations in the successor set `succ`.
+///
+/// [`mem_barrier`](fn@crate::mem_barrier) is implemented in terms of this function.
+///
+/// If either set is empty, the `fence` would not order anything.
+/// Since this is most likely a mistake, this function runs a full `fence iorw, iorw` instead.
+///
+/// The fence instruction encodes both sets as immediates.
+/// This function should be called with constant arguments, which allows it to compile to a single instruction.
+///
+/// # Common fences
+///
+/// These fences implement the Linux barriers on RISC-V:
+///
+/// | Linux function                | [`mem_barrier`](fn@crate::mem_barrier) | Fence              |
+/// | ----------------------------- | -------------------------------------- | ------------------ |
+/// | `mb`                          | `Mmio`, `General`                      | `fence iorw, iorw` |
+/// | `rmb`                         | `Mmio`, `Read`                         | `fence ir, ir`     |
+/// | `wmb`                         | `Mmio`, `Write`                        | `fence ow, ow`     |
+/// | —                             | `Mmio`, `Acquire`                      | `fence ir, iorw`   |
+/// | —                             | `Mmio`, `Release`                      | `fence iorw, ow`   |
+/// | `smp_mb`, `dma_mb`            | `Smp` or `Dma`, `General`              | `fence rw, rw`     |
+/// | `smp_rmb`, `dma_rmb`          | `Smp` or `Dma`, `Read`                 | `fence r, r`       |
+/// | `smp_wmb`, `dma_wmb`          | `Smp` or `Dma`, `Write`                | `fence w, w`       |
+/// | `smp_load_acquire`            | `Smp` or `Dma`, `Acquire`              | `fence r, rw`      |
+/// | `smp_store_release`           | `Smp` or `Dma`, `Release`              | `fence rw, w`      |
+/// | —                             | `Device`, `General`                    | `fence io, io`     |
+/// | —                             | `Device`, `Read`                       | `fence i, i`       |
+/// | —                             | `Device`, `Write`                      | `fence o, o`       |
+/// | —                             | `Device`, `Acquire`                    | `fence i, io`      |
+/// | —                             | `Device`, `Release`                    | `fence io, o`      |
+/// | after `readl` (`__io_ar`)     | —                                      | `fence i, ir`      |
+/// | before `writel` (`__io_bw`)   | —                                      | `fence w, o`       |
+/// | before `inl` (`__io_pbr`)     | —                                      | `fence io, i`      |
+/// | after `inl` (`__io_par`)      | —                                      | `fence i, ior`     |
+/// | before `outl` (`__io_pbw`)    | —                                      | `fence iow, o`     |
+/// | after `outl` (`__io_paw`)     | —                                      | `fence o, io`      |
+///
+/// An acquire fence follows the load it applies to, and a release fence precedes the store it applies to.
+/// Sequentially consistent atomics need `fence rw, rw` around the access.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+/// # {
+/// use mem_barrier::riscv::{FenceSet, fence};
+///
+/// // Acquire: order an earlier load before later loads and stores.
+/// fence(FenceSet::R, FenceSet::R | FenceSet::W);
+///
+/// // Release: order earlier loads and stores before a later store.
+/// fence(FenceSet::R | FenceSet::W, FenceSet::W);
+///
+/// // Order memory writes, such as a DMA descriptor, before an MMIO write that notifies the device.
+/// fence(FenceSet::W, FenceSet::O);
+/// # }
+/// ```
+#[inline(always)]
+pub fn fence(pred: FenceSet, succ: FenceSet) {
+    let (pred, succ) = if pred.is_empty() || succ.is_empty() {
+        (FenceSet::all(), FenceSet::all())
+    } else {
+        (pred, succ)
+    };
+
+    match pred.bits() {
+        0x1 => fence_pred::<0x1>(succ),
+        0x2 => fence_pred::<0x2>(succ),
+        0x3 => fence_pred::<0x3>(succ),
+        0x4 => fence_pred::<0x4>(succ),
+        0x5 => fence_pred::<0x5>(succ),
+        0x6 => fence_pred::<0x6>(succ),
+        0x7 => fence_pred::<0x7>(succ),
+        0x8 => fence_pred::<0x8>(succ),
+        0x9 => fence_pred::<0x9>(succ),
+        0xa => fence_pred::<0xa>(succ),
+        0xb => fence_pred::<0xb>(succ),
+        0xc => fence_pred::<0xc>(succ),
+        0xd => fence_pred::<0xd>(succ),
+        0xe => fence_pred::<0xe>(succ),
+        _ => fence_pred::<0xf>(succ),
+    }
+}
+
+#[inline(always)]
+fn fence_pred<const PRED: u8>(succ: FenceSet) {
+    match succ.bits() {
+        0x1 => fence_raw::<PRED, 0x1>(),
+        0x2 => fence_raw::<PRED, 0x2>(),
+        0x3 => fence_raw::<PRED, 0x3>(),
+        0x4 => fence_raw::<PRED, 0x4>(),
+        0x5 => fence_raw::<PRED, 0x5>(),
+        0x6 => fence_raw::<PRED, 0x6>(),
+        0x7 => fence_raw::<PRED, 0x7>(),
+        0x8 => fence_raw::<PRED, 0x8>(),
+        0x9 => fence_raw::<PRED, 0x9>(),
+        0xa => fence_raw::<PRED, 0xa>(),
+        0xb => fence_raw::<PRED, 0xb>(),
+        0xc => fence_raw::<PRED, 0xc>(),
+        0xd => fence_raw::<PRED, 0xd>(),
+        0xe => fence_raw::<PRED, 0xe>(),
+        _ => fence_raw::<PRED, 0xf>(),
+    }
+}
+
+#[cfg(any(miri, feature = "loom"))]
+#[inline(always)]
+fn fence_raw<const PRED: u8, const SUCC: u8>() {
+    // Miri cannot run inline assembly, and loom cannot reason about it.
+    crate::arch::fallback::fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(not(any(miri, feature = "loom")))]
+#[inline(always)]
+fn fence_raw<const PRED: u8, const SUCC: u8>() {
+    // The assembler only accepts symbolic sets, so we emit the encoding directly.
+    // `fence` is a MISC-MEM I-type instruction with `fm = 0`, `pred` in bits 27:24, and `succ` in bits 23:20.
+    //
+    // SAFETY: This is just a memory ordering fence.
+    unsafe {
+        core::arch::asm!(
+            ".insn i 0x0f, 0, x0, x0, {imm}",
+            imm = const ((PRED as i16) << 4) | SUCC as i16,
+            options(preserves_flags, nostack)
+        );
+    }
+}
+
+/// A TSO fence.
+///
+/// This runs a `fence.tso` instruction.
+/// It orders earlier loads before later loads and stores, and earlier stores before later stores.
+/// Unlike `fence rw, rw`, it does not order earlier stores before later loads.
+///
+/// `fence.tso` is part of the base ISA.
+/// Cores that do not implement it execute it as `fence rw, rw`.
+///
+/// # Current implementation
+///
+/// On cores with the Ztso extension, memory is already ordered this way, so this is a compiler barrier only.
+#[inline(always)]
+pub fn fence_tso() {
+    cfg_if::cfg_if! {
+        if #[cfg(any(miri, feature = "loom"))] {
+            // Miri cannot run inline assembly, and loom cannot reason about it.
+            crate::arch::fallback::fence(core::sync::atomic::Ordering::AcqRel);
+        } else if #[cfg(target_feature = "ztso")] {
+            crate::compiler_barrier();
+        } else {
+            // SAFETY: This is just a memory ordering fence.
+            unsafe {
+                core::arch::asm!("fence.tso", options(preserves_flags, nostack));
+            }
+        }
+    }
+}
+
+/// An instruction fetch fence.
+///
+/// This runs a `fence.i` instruction.
+/// It makes later instruction fetches on this hart observe earlier stores to instruction memory, such as when loading or generating code.
+///
+/// `fence.i` only synchronizes the local hart.
+/// It is not a cross-hart barrier: other harts may still execute stale instructions.
+/// To synchronize instruction fetches on other harts, each of them has to run `fence.i` itself after the stores are visible to it, for example, via an interprocessor interrupt.
+///
+/// `fence.i` requires the Zifencei extension.
+///
+/// This is the same as [`mem_barrier`](fn@crate::mem_barrier) with [`BarrierKind::Instruction`](crate::BarrierKind::Instruction).
+#[inline(always)]
+pub fn fence_i() {
+    crate::mem_barrier(crate::BarrierKind::Instruction, crate::BarrierType::General);
+}
@@ -0,0 +1,328 @@
+//! x86-specific CPU feature detection and barriers.
+
+use core::fmt;
+use core::ops::{BitOr, BitOrAssign};
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::{BarrierKind, BarrierType};
+
+/// A set of CPU features that affect memory barriers.
+///
+/// Sets can be combined with `|`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+/// # {
+/// use mem_barrier::x86::{self, Features};
+///
+/// if x86::features().contains(Features::SERIALIZE) {
+///     // `x86::mem_barrier_with` runs `serialize` instead of `cpuid` for instruction barriers.
+/// }
+/// # }
+/// ```
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+pub struct Features(u8);
+
+impl Features {
+    /// The SSE2 extension, which adds the [LFENCE] and [MFENCE] instructions.
+    ///
+    /// All x86-64 processors support SSE2.
+    ///
+    /// [LFENCE]: https://www.felixcloutier.com/x86/lfence
+    /// [MFENCE]: https://www.felixcloutier.com/x86/mfence
+    pub const SSE2: Self = Self(0b01);
+
+    /// The [SERIALIZE] instruction.
+    ///
+    /// [SERIALIZE]: https://www.felixcloutier.com/x86/serialize
+    pub const SERIALIZE: Self = Self(0b10);
+
+    /// Returns an empty set.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns the raw bits of this set.
+    #[inline]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns `true` if this set is empty.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns `true` if this set contains all features of `other`.
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the union of both sets.
+    #[inline]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOr for Features {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl BitOrAssign for Features {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = self.union(rhs);
+    }
+}
+
+impl fmt::Debug for Features {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Features(")?;
+        if self.is_empty() {
+            f.write_str("empty")?;
+        }
+        let mut first = true;
+        for (set, name) in [(Self::SSE2, "sse2"), (Self::SERIALIZE, "serialize")] {
+            if self.contains(set) {
+                if !first {
+                    f.write_str(" | ")?;
+                }
+                f.write_str(name)?;
+                first = false;
+            }
+        }
+        f.write_str(")")
+    }
+}
+
+/// Marks [`FEATURES`] as detected, since the detected set may be empty.
+const DETECTED: u8 = 0b1000_0000;
+
+/// The detected features, or zero before detection.
+static FEATURES: AtomicU8 = AtomicU8::new(0);
+
+/// Returns the features of the current processor.
+///
+/// The first call detects the features using [CPUID] and caches them in an atomic.
+/// Later calls only load the cached features.
+/// This does not block: threads that race on the first call detect the features independently, which yields the same result.
+///
+/// On processors without CPUID, which includes some 486-class processors, this returns an empty set.
+/// Under Miri, this returns an empty set as well.
+///
+/// [`mem_barrier`](fn@crate::mem_barrier) does not consult these features, since it selects its instructions at build time.
+/// Pass them to [`mem_barrier_with`] to select instructions at runtime instead.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+/// # {
+/// use mem_barrier::x86::{self, Features};
+///
+/// let features = x86::features();
+/// # #[cfg(all(target_arch = "x86_64", not(miri)))]
+/// assert!(features.contains(Features::SSE2));
+/// # }
+/// ```
+///
+/// [CPUID]: https://www.felixcloutier.com/x86/cpuid
+/// [SERIALIZE]: https://www.felixcloutier.com/x86/serialize
+#[inline]
+pub fn features() -> Features {
+    cfg_if::cfg_if! {
+        if #[cfg(miri)] {
+            // Miri cannot run inline assembly.
+            Features::empty()
+        } else {
+            // The features never change, so relaxed ordering suffices.
+            let bits = FEATURES.load(Ordering::Relaxed);
+            if bits & DETECTED != 0 {
+                return Features(bits & !DETECTED);
+            }
+
+            let features = detect();
+            FEATURES.store(features.0 | DETECTED, Ordering::Relaxed);
+            features
+        }
+    }
+}
+
+/// Detects the features of the current processor.
+#[cfg(not(miri))]
+#[cold]
+#[inline(never)]
+fn detect() -> Features {
+    let mut features = Features::empty();
+
+    if !has_cpuid() {
+        return features;
+    }
+
+    let (max_leaf, _) = cpuid(0);
+    if max_leaf >= 1 {
+        let (_, edx) = cpuid(1);
+        if edx & (1 << 26) != 0 {
+            features |= Features::SSE2;
+        }
+    }
+    if max_leaf >= 7 {
+        let (_, edx) = cpuid(7);
+        if edx & (1 << 14) != 0 {
+            features |= Features::SERIALIZE;
+        }
+    }
+
+    features
+}
+
+/// Returns whether the processor implements `cpuid`.
+///
+/// All x86-64 processors implement `cpuid`.
+#[cfg(all(target_arch = "x86_64", not(miri)))]
+#[inline(always)]
+fn has_cpuid() -> bool {
+    true
+}
+
+/// Returns whether the processor implements `cpuid`.
+///
+/// A processor implements `cpuid` if software can toggle the ID flag, which is bit 21 of EFLAGS.
+#[cfg(all(target_arch = "x86", not(miri)))]
+#[inline(always)]
+fn has_cpuid() -> bool {
+    let changed: u32;
+
+    // SAFETY: This toggles the ID flag and restores EFLAGS afterward.
+    unsafe {
+        core::arch::asm!(
+            "pushfd",
+            "pop {orig:e}",
+            "mov {changed:e}, {orig:e}",
+            "xor {changed:e}, 0x200000",
+            "push {changed:e}",
+            "popfd",
+            "pushfd",
+            "pop {changed:e}",
+            "push {orig:e}",
+            "popfd",
+            "xor {changed:e}, {orig:e}",
+            orig = out(reg) _,
+            changed = out(reg) changed,
+        );
+    }
+
+    changed & (1 << 21) != 0
+}
+
+/// Runs `cpuid` with the given leaf and subleaf 0 and returns `eax` and `edx`.
+#[cfg(all(feature = "stdarch", not(miri)))]
+#[inline(always)]
+fn cpuid(leaf: u32) -> (u32, u32) {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::__cpuid_count;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::__cpuid_count;
+
+    let result = __cpuid_count(leaf, 0);
+    (result.eax, result.edx)
+}
+
+/// Runs `cpuid` with the given leaf and subleaf 0 and returns `eax` and `edx`.
+#[cfg(all(not(feature = "stdarch"), not(miri)))]
+#[inline(always)]
+fn cpuid(leaf: u32) -> (u32, u32) {
+    let eax;
+    let edx;
+
+    // `cpuid` writes to `eax`, `ebx`, `ecx`, and `edx`.
+    // LLVM reserves `rbx`, so we have to save and restore it manually.
+    //
+    // SAFETY: `cpuid` only writes to the declared registers.
+    unsafe {
+        #[cfg(target_arch = "x86")]
+        core::arch::asm!(
+            "mov {tmp:e}, ebx",
+            "cpuid",
+            "mov ebx, {tmp:e}",
+            tmp = out(reg) _,
+            inout("eax") leaf => eax,
+            inout("ecx") 0 => _,
+            out("edx") edx,
+            options(nomem, preserves_flags, nostack)
+        );
+        #[cfg(target_arch = "x86_64")]
+        core::arch::asm!(
+            "mov {tmp:r}, rbx",
+            "cpuid",
+            "mov rbx, {tmp:r}",
+            tmp = out(reg) _,
+            inout("eax") leaf => eax,
+            inout("ecx") 0 => _,
+            out("edx") edx,
+            options(nomem, preserves_flags, nostack)
+        );
+    }
+
+    (eax, edx)
+}
+
+/// A memory barrier that selects its instructions based on the provided features.
+///
+/// This is the same as [`mem_barrier`](fn@crate::mem_barrier), except for the following:
+///
+/// - [`BarrierKind::Instruction`] barriers run a [SERIALIZE] instruction if `features` contains [`Features::SERIALIZE`].
+/// - On x86 without SSE2 at build time, [`BarrierKind::Mmio`] and [`BarrierKind::Dma`] barriers run fences instead of a locked instruction if `features` contains [`Features::SSE2`].
+///
+/// Pass [`features()`] to select instructions for the current processor.
+/// Unlike [`mem_barrier`](fn@crate::mem_barrier), this checks the features at runtime, and [`barrier_mnemonic`](crate::barrier_mnemonic) does not reflect that selection.
+/// If `features` contains features that the processor does not implement, the barrier raises an invalid-opcode exception.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+/// # {
+/// use mem_barrier::{BarrierKind, BarrierType, x86};
+///
+/// let features = x86::features();
+///
+/// // Patch code here.
+///
+/// x86::mem_barrier_with(features, BarrierKind::Instruction, BarrierType::General);
+/// # }
+/// ```
+///
+/// [SERIALIZE]: https://www.felixcloutier.com/x86/serialize
+#[inline(always)]
+pub fn mem_barrier_with(features: Features, kind: BarrierKind, ty: BarrierType) {
+    cfg_if::cfg_if! {
+        if #[cfg(any(miri, feature = "loom"))] {
+            // Miri cannot run inline assembly, and loom cannot reason about it.
+            let _ = features;
+            crate::mem_barrier(kind, ty);
+        } else {
+            use crate::{ArchBarrier, arch};
+
+            #[cfg(feature = "trace")]
+            crate::trace(kind, ty);
+
+            match crate::barrier(kind) {
+                ArchBarrier::Cpu(kind) => arch::mem_barrier_with(features, kind, ty),
+                ArchBarrier::Compiler => arch::compiler_fence(ty),
+                ArchBarrier::Instruction => arch::instruction_barrier_with(features),
+            }
+        }
+    }
+}
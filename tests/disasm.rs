@@ -0,0 +1,269 @@
+//! Checks the instructions emitted for each memory barrier by disassembling the `disasm` example.
+//!
+//! This test is skipped if objdump is not available.
+//! To use a specific objdump, set the `OBJDUMP` environment variable.
+
+#![cfg(not(any(miri, feature = "loom")))]
+
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use mem_barrier::{BarrierKind, BarrierType, barrier_mnemonic};
+
+/// The features that affect the emitted instructions.
+const FEATURES: &[(&str, bool)] = &[
+    ("fallback", cfg!(feature = "fallback")),
+    ("nightly", cfg!(feature = "nightly")),
+    ("portable-atomic", cfg!(feature = "portable-atomic")),
+    ("single_core", cfg!(feature = "single_core")),
+    ("stdarch", cfg!(feature = "stdarch")),
+    ("x86-lock-fence", cfg!(feature = "x86-lock-fence")),
+];
+
+#[test]
+fn test_disasm() {
+    let Some(disassembly) = Disassembly::get() else {
+        return;
+    };
+
+    for &kind in BarrierKind::all() {
+        for &ty in BarrierType::all() {
+            let name = format!("barrier_{kind}_{ty}");
+            let instructions = disassembly.function(&name);
+            let mnemonic = normalize(barrier_mnemonic(kind, ty));
+
+            if mnemonic.is_empty() {
+                assert_eq!(
+                    instructions.len(),
+                    1,
+                    "`{name}` should only return: {instructions:?}"
+                );
+            } else {
+                assert!(
+                    instructions.contains(&mnemonic),
+                    "`{name}` should contain `{}`: {instructions:?}",
+                    mnemonic.join(" ")
+                );
+            }
+
+            assert!(
+                !instructions.iter().any(|instruction| is_call(instruction)),
+                "`{name}` should be inlined: {instructions:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_compiler_barrier() {
+    let Some(disassembly) = Disassembly::get() else {
+        return;
+    };
+
+    for &ty in BarrierType::all() {
+        let name = format!("barrier_compiler_{ty}");
+        let instructions = disassembly.function(&name);
+        assert_eq!(
+            instructions,
+            [["ret"]],
+            "`{name}` should only return: {instructions:?}"
+        );
+    }
+}
+
+#[test]
+fn test_full_system_barrier() {
+    let Some(disassembly) = Disassembly::get() else {
+        return;
+    };
+
+    // This pins the strongest data memory barrier of each architecture, independent of the barrier kinds.
+    let mnemonic = if cfg!(target_arch = "aarch64") {
+        "dsb sy"
+    } else if cfg!(any(target_arch = "riscv32", target_arch = "riscv64")) {
+        "fence iorw, iorw"
+    } else if cfg!(any(target_arch = "x86_64", target_feature = "sse2")) {
+        "mfence"
+    } else if cfg!(target_arch = "x86") {
+        "lock add dword ptr [esp], 0"
+    } else {
+        return;
+    };
+
+    let name = "barrier_full_system";
+    let instructions = disassembly.function(name);
+    assert!(
+        instructions.contains(&normalize(mnemonic)),
+        "`{name}` should contain `{mnemonic}`: {instructions:?}"
+    );
+    assert!(
+        !instructions.iter().any(|instruction| is_call(instruction)),
+        "`{name}` should be inlined: {instructions:?}"
+    );
+}
+
+/// The disassembly of the `disasm` example.
+struct Disassembly {
+    /// The section of each symbol.
+    symbols: HashMap<&'static str, &'static str>,
+
+    /// The normalized instructions of each section.
+    sections: HashMap<&'static str, Vec<Vec<String>>>,
+}
+
+impl Disassembly {
+    /// Compiles and disassembles the `disasm` example once.
+    ///
+    /// Returns `None` if objdump is not available.
+    fn get() -> Option<&'static Self> {
+        static DISASSEMBLY: OnceLock<Option<Disassembly>> = OnceLock::new();
+
+        DISASSEMBLY
+            .get_or_init(|| {
+                let Some(objdump) = objdump() else {
+                    eprintln!("skipping: objdump is not available");
+                    return None;
+                };
+
+                let obj = compile();
+                let symbols = objdump_output(&objdump, &["-t"], &obj).leak();
+                let sections = objdump_output(&objdump, &["-d", "--no-show-raw-insn"], &obj).leak();
+
+                Some(Self {
+                    symbols: symbols_to_sections(symbols),
+                    sections: section_instructions(sections),
+                })
+            })
+            .as_ref()
+    }
+
+    /// Returns the normalized instructions of a function.
+    fn function(&self, name: &str) -> &[Vec<String>] {
+        // Identical functions may be merged, so we look up the section of each symbol.
+        self.symbols
+            .get(name)
+            .and_then(|section| self.sections.get(section))
+            .unwrap_or_else(|| panic!("`{name}` not found in disassembly"))
+    }
+}
+
+/// Returns the first objdump that runs.
+fn objdump() -> Option<OsString> {
+    env::var_os("OBJDUMP")
+        .into_iter()
+        .chain(["llvm-objdump".into(), "objdump".into()])
+        .find(|objdump| {
+            Command::new(objdump)
+                .arg("--version")
+                .output()
+                .is_ok_and(|output| output.status.success())
+        })
+}
+
+/// Compiles the `disasm` example with the features of this test and returns the path of its object file.
+fn compile() -> PathBuf {
+    let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("disasm");
+    let obj = out_dir.join("disasm.o");
+    let features = FEATURES
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(feature, _)| *feature)
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let status = Command::new(env!("CARGO"))
+        .args(["rustc", "--release", "--example", "disasm"])
+        .arg("--manifest-path")
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&out_dir)
+        .args(["--no-default-features", "--features", &features])
+        .args(["--", "-Ccodegen-units=1"])
+        .arg(format!("--emit=obj={}", obj.display()))
+        .status()
+        .unwrap();
+    assert!(status.success(), "failed to compile the `disasm` example");
+
+    obj
+}
+
+/// Runs objdump on the object file and returns its output.
+fn objdump_output(objdump: &OsString, args: &[&str], obj: &Path) -> String {
+    let mut command = Command::new(objdump);
+    command.args(args);
+    if cfg!(any(target_arch = "x86", target_arch = "x86_64")) {
+        command.args(["-M", "intel"]);
+    }
+    let output = command.arg(obj).output().unwrap();
+    assert!(output.status.success(), "failed to run objdump");
+
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Returns the section of each symbol in the symbol table.
+fn symbols_to_sections(symbols: &str) -> HashMap<&str, &str> {
+    symbols
+        .lines()
+        .filter_map(|line| {
+            let (left, right) = line.split_once('\t')?;
+            let section = left.split_whitespace().last()?;
+            let name = right.split_whitespace().nth(1)?;
+            Some((name, section))
+        })
+        .collect()
+}
+
+/// Returns the normalized instructions of each section in the disassembly.
+fn section_instructions(disasm: &str) -> HashMap<&str, Vec<Vec<String>>> {
+    let mut sections: HashMap<&str, Vec<Vec<String>>> = HashMap::new();
+    let mut current = None;
+
+    for line in disasm.lines() {
+        if let Some(section) = line
+            .strip_prefix("Disassembly of section ")
+            .and_then(|line| line.strip_suffix(':'))
+        {
+            current = Some(sections.entry(section).or_default());
+        } else if let Some((address, instruction)) = line.split_once(':') {
+            let address = address.trim();
+            if address.is_empty() || !address.chars().all(|c| c.is_ascii_hexdigit()) {
+                continue;
+            }
+            if let Some(instructions) = &mut current {
+                let instruction = normalize(instruction);
+                // LLVM prints x86 instruction prefixes on their own lines.
+                match instructions.last_mut() {
+                    Some(prefix) if *prefix == ["lock"] => prefix.extend(instruction),
+                    _ => instructions.push(instruction),
+                }
+            }
+        }
+    }
+
+    sections
+}
+
+/// Returns `true` if the instruction calls or jumps to another function.
+fn is_call(instruction: &[String]) -> bool {
+    let mnemonic = instruction.first().map_or("", String::as_str);
+    ["b", "bl", "call", "j", "jal", "jmp", "tail"].contains(&mnemonic)
+}
+
+/// Splits an instruction into lowercase tokens and writes hexadecimal numbers as decimal numbers.
+///
+/// This makes the output of different disassemblers comparable with the mnemonics of this crate.
+fn normalize(instruction: &str) -> Vec<String> {
+    instruction
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .map(|token| match token.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).map_or(token.to_string(), |n| n.to_string()),
+            None => token.to_string(),
+        })
+        .collect()
+}